@@ -0,0 +1,317 @@
+use crate::{Button, Player, PlayerButton};
+use bevy::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+#[cfg(feature = "serialize")]
+use std::{io, path::Path};
+
+/// Keyboard keys used for each player's buttons when no controller is plugged in
+///
+/// Defaults to the mappings in the source for [`PlayerButton`]. Insert your
+/// own copy of this resource to rebind keys.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{Button, KeyboardBindings, Player};
+///
+/// let mut bindings = KeyboardBindings::default();
+/// bindings.bind(Player::P1, Button::A1, KeyCode::Z);
+/// assert_eq!(bindings.key(Player::P1, Button::A1), Some(KeyCode::Z));
+/// assert_eq!(bindings.key(Player::P1, Button::A2), Some(KeyCode::W));
+///
+/// App::new().insert_resource(bindings);
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct KeyboardBindings {
+  bindings: HashMap<Player, HashMap<Button, Option<KeyCode>>>,
+  #[cfg_attr(feature = "serialize", serde(default))]
+  modifiers: HashMap<Player, KeyCode>,
+}
+
+impl Default for KeyboardBindings {
+  fn default() -> Self {
+    let bindings = enum_iterator::all::<Player>()
+      .map(|player| {
+        let keys = enum_iterator::all::<Button>()
+          .map(|button| {
+            let key = KeyCode::from(PlayerButton { player, button });
+            (button, Some(key))
+          })
+          .collect();
+        (player, keys)
+      })
+      .collect();
+    Self {
+      bindings,
+      modifiers: HashMap::new(),
+    }
+  }
+}
+
+impl KeyboardBindings {
+  /// The default bindings, except [`Button::Menu`] is on F1 for P1 and F2 for
+  /// P2 instead of both being on Escape
+  ///
+  /// Menu usually quits the game, so this keeps a stray Escape during
+  /// development from closing it. Any player's Menu key can also be moved
+  /// with [`KeyboardBindings::bind`].
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::{input::InputPlugin, prelude::*};
+  /// use devcaders::{Button, DevcadeControls, KeyboardBindings, Player};
+  ///
+  /// #[derive(Resource, Default)]
+  /// struct Menu(Vec<Player>);
+  ///
+  /// fn input_system(input: DevcadeControls, mut menu: ResMut<Menu>) {
+  ///   menu.0 = enum_iterator::all::<Player>()
+  ///     .filter(|&player| input.pressed(player, Button::Menu))
+  ///     .collect();
+  /// }
+  ///
+  /// let mut app = App::new();
+  /// app
+  ///   .add_plugins((MinimalPlugins, InputPlugin))
+  ///   .insert_resource(KeyboardBindings::development())
+  ///   .init_resource::<Menu>()
+  ///   .add_systems(Update, input_system);
+  ///
+  /// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+  /// app.update();
+  /// assert!(app.world.resource::<Menu>().0.is_empty());
+  ///
+  /// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::F2);
+  /// app.update();
+  /// assert_eq!(app.world.resource::<Menu>().0, [Player::P2]);
+  /// ```
+  pub fn development() -> Self {
+    let mut bindings = Self::default();
+    bindings.bind(Player::P1, Button::Menu, KeyCode::F1);
+    bindings.bind(Player::P2, Button::Menu, KeyCode::F2);
+    bindings
+  }
+
+  /// Returns the key bound to the given player's button, if there is one
+  pub fn key(&self, player: Player, button: Button) -> Option<KeyCode> {
+    self
+      .bindings
+      .get(&player)
+      .and_then(|keys| keys.get(&button))
+      .copied()
+      .flatten()
+  }
+
+  /// Binds the given player's button to `key`, replacing the previous binding
+  pub fn bind(&mut self, player: Player, button: Button, key: KeyCode) {
+    self
+      .bindings
+      .entry(player)
+      .or_default()
+      .insert(button, Some(key));
+  }
+
+  /// Removes the binding for the given player's button so it is never pressed
+  /// when using the keyboard
+  pub fn unbind(&mut self, player: Player, button: Button) {
+    self
+      .bindings
+      .entry(player)
+      .or_default()
+      .insert(button, None);
+  }
+
+  /// Makes the player's keys only count while `modifier` is held, or removes
+  /// their modifier if it's `None`
+  ///
+  /// While any player's modifier is held, the keys of players without one
+  /// don't count. This lets players share the same keys, with one layout
+  /// plain and the other behind a modifier, for testing several players on
+  /// one keyboard.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::{Button, KeyboardBindings, Player};
+  ///
+  /// // Both players on the same keys, P2 while holding shift
+  /// let mut bindings = KeyboardBindings::default();
+  /// for button in Button::all() {
+  ///   let key = bindings.key(Player::P1, button).unwrap();
+  ///   bindings.bind(Player::P2, button, key);
+  /// }
+  /// bindings.set_modifier(Player::P2, Some(KeyCode::ShiftLeft));
+  ///
+  /// let mut keyboard = Input::<KeyCode>::default();
+  /// keyboard.press(KeyCode::Q);
+  /// assert!(bindings.pressed(Player::P1, Button::A1, &keyboard));
+  /// assert!(!bindings.pressed(Player::P2, Button::A1, &keyboard));
+  ///
+  /// keyboard.press(KeyCode::ShiftLeft);
+  /// assert!(!bindings.pressed(Player::P1, Button::A1, &keyboard));
+  /// assert!(bindings.pressed(Player::P2, Button::A1, &keyboard));
+  /// ```
+  pub fn set_modifier(&mut self, player: Player, modifier: Option<KeyCode>) {
+    match modifier {
+      Some(modifier) => self.modifiers.insert(player, modifier),
+      None => self.modifiers.remove(&player),
+    };
+  }
+
+  /// Returns the key the player holds to use their keys, if they need one
+  pub fn modifier(&self, player: Player) -> Option<KeyCode> {
+    self.modifiers.get(&player).copied()
+  }
+
+  /// Returns true if the player's button is pressed on `keyboard`, taking
+  /// [modifiers](KeyboardBindings::set_modifier) into account
+  pub fn pressed(&self, player: Player, button: Button, keyboard: &Input<KeyCode>) -> bool {
+    let Some(key) = self.key(player, button) else {
+      return false;
+    };
+    let modifier_held = match self.modifier(player) {
+      Some(modifier) => keyboard.pressed(modifier),
+      None => !keyboard.any_pressed(self.modifiers.values().copied()),
+    };
+    modifier_held && keyboard.pressed(key)
+  }
+
+  /// Overwrites bindings and modifiers in `self` with every one in `other`,
+  /// leaving buttons that `other` doesn't bind or unbind untouched
+  pub fn merge(&mut self, other: KeyboardBindings) {
+    for (player, keys) in other.bindings {
+      self.bindings.entry(player).or_default().extend(keys);
+    }
+    self.modifiers.extend(other.modifiers);
+  }
+}
+
+#[cfg(feature = "serialize")]
+impl KeyboardBindings {
+  /// Loads bindings from the RON file at `path`, merged over the defaults.
+  ///
+  /// The file only needs to list the bindings that differ from the defaults,
+  /// with `None` for unbound buttons, along with any modifiers. If the file is missing, the defaults are returned. If it can't be read
+  /// or parsed, the error is logged and the defaults are returned.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::{Button, KeyboardBindings, Player};
+  ///
+  /// let path = std::env::temp_dir().join(format!(
+  ///   "devcaders-load-or-default-{}.ron",
+  ///   std::process::id()
+  /// ));
+  ///
+  /// // Missing files give the defaults
+  /// let _ = std::fs::remove_file(&path);
+  /// assert_eq!(KeyboardBindings::load_or_default(&path), KeyboardBindings::default());
+  ///
+  /// // Partial files are merged over the defaults
+  /// std::fs::write(&path, "(bindings: {P2: {Menu: Back}})").unwrap();
+  /// let bindings = KeyboardBindings::load_or_default(&path);
+  /// assert_eq!(bindings.key(Player::P2, Button::Menu), Some(KeyCode::Back));
+  /// assert_eq!(bindings.key(Player::P2, Button::A1), Some(KeyCode::Y));
+  /// assert_eq!(bindings.key(Player::P1, Button::Menu), Some(KeyCode::Escape));
+  ///
+  /// // Malformed files give the defaults
+  /// std::fs::write(&path, "(bindings: {P2: {Menu: ").unwrap();
+  /// assert_eq!(KeyboardBindings::load_or_default(&path), KeyboardBindings::default());
+  /// # std::fs::remove_file(&path).unwrap();
+  /// ```
+  pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+    let path = path.as_ref();
+    let mut bindings = Self::default();
+    let contents = match std::fs::read_to_string(path) {
+      Ok(contents) => contents,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => return bindings,
+      Err(err) => {
+        log::error!("Couldn't read keyboard bindings from {path:?}, using defaults. {err}");
+        return bindings;
+      }
+    };
+    match ron_options().from_str(&contents) {
+      Ok(overrides) => bindings.merge(overrides),
+      Err(err) => {
+        log::error!("Couldn't parse keyboard bindings in {path:?}, using defaults. {err}");
+      }
+    }
+    bindings
+  }
+
+  /// Saves these bindings as a RON file at `path` which can be read with
+  /// [`KeyboardBindings::load_or_default`]
+  pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    let contents = ron_options()
+      .to_string_pretty(self, Default::default())
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    std::fs::write(path, contents)
+  }
+}
+
+/// Lets bound keys be written without `Some(..)`
+#[cfg(feature = "serialize")]
+fn ron_options() -> ron::Options {
+  ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+}
+
+#[cfg(all(test, feature = "serialize"))]
+mod tests {
+  use super::*;
+
+  fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("devcaders-{name}-{}.ron", std::process::id()))
+  }
+
+  #[test]
+  fn saved_bindings_load_back_unchanged() {
+    let path = temp_path("saved-bindings");
+    let mut bindings = KeyboardBindings::default();
+    bindings.bind(Player::P2, Button::Menu, KeyCode::Back);
+    bindings.unbind(Player::P1, Button::B4);
+    bindings.save(&path).unwrap();
+
+    let loaded = KeyboardBindings::load_or_default(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, bindings);
+    assert_eq!(loaded.key(Player::P1, Button::B4), None);
+    assert_eq!(loaded.key(Player::P2, Button::Menu), Some(KeyCode::Back));
+  }
+
+  #[test]
+  fn unbinds_in_files_remove_default_bindings() {
+    let path = temp_path("unbinds");
+    std::fs::write(&path, "(bindings: {P1: {B4: None, Menu: Back}})").unwrap();
+    let bindings = KeyboardBindings::load_or_default(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(bindings.key(Player::P1, Button::B4), None);
+    assert_eq!(bindings.key(Player::P1, Button::Menu), Some(KeyCode::Back));
+    assert_eq!(bindings.key(Player::P1, Button::A1), Some(KeyCode::Q));
+  }
+
+  #[test]
+  fn shared_keys_keep_their_modifiers_after_a_reload() {
+    let path = temp_path("shared-keys");
+    let mut bindings = KeyboardBindings::default();
+    for button in Button::all() {
+      let key = bindings.key(Player::P1, button).unwrap();
+      bindings.bind(Player::P2, button, key);
+    }
+    bindings.set_modifier(Player::P2, Some(KeyCode::ShiftLeft));
+    bindings.save(&path).unwrap();
+
+    let loaded = KeyboardBindings::load_or_default(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, bindings);
+    assert_eq!(loaded.modifier(Player::P2), Some(KeyCode::ShiftLeft));
+
+    let mut keyboard = Input::<KeyCode>::default();
+    keyboard.press(KeyCode::Q);
+    assert!(loaded.pressed(Player::P1, Button::A1, &keyboard));
+    assert!(!loaded.pressed(Player::P2, Button::A1, &keyboard));
+  }
+}
@@ -0,0 +1,266 @@
+use crate::Player;
+use bevy::prelude::*;
+
+/// Hides input from the game while it's being captured for something else,
+/// like a screen for rebinding buttons
+///
+/// While [`InputCaptureMode::active`] is set,
+/// [`DevcadeControls`](crate::DevcadeControls) reports every button as
+/// released and the stick as centered, so pressing a button to bind it
+/// doesn't also make the game act on it. The presses can still be read with
+/// [`DevcadeControls::captured`](crate::DevcadeControls::captured).
+///
+/// Buttons that are still held when capture ends read as pressed straight
+/// away, without being [just pressed](crate::DevcadeControls::just_pressed).
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{DevcadeControls, InputCaptureMode};
+///
+/// fn start_rebinding(mut capture: ResMut<InputCaptureMode>) {
+///   capture.active = true;
+/// }
+///
+/// fn rebind_system(input: DevcadeControls) {
+///   if let Some((player, button)) = input.captured() {
+///     println!("{player:?} pressed {button:?} to rebind it");
+///   }
+/// }
+/// ```
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputCaptureMode {
+  /// Whether input is being captured. Defaults to false
+  pub active: bool,
+}
+
+/// Drops all input while set, e.g. while a level is loading, so presses made
+/// in the meantime don't take effect as soon as gameplay starts
+///
+/// While paused, [`DevcadeControls`](crate::DevcadeControls) reports every
+/// button of both players as released and the stick as centered, and nothing
+/// is [just pressed](crate::DevcadeControls::just_pressed) or released.
+/// Unlike [`InputCaptureMode`], nothing can be
+/// [captured](crate::DevcadeControls::captured) either. Buttons pressed
+/// while paused and still held after read as pressed straight away, without
+/// being just pressed.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::InputPaused;
+///
+/// fn start_loading_system(mut paused: ResMut<InputPaused>) {
+///   paused.0 = true;
+/// }
+/// ```
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputPaused(pub bool);
+
+/// Which players' input [`DevcadeControls`](crate::DevcadeControls) reports,
+/// for freezing one player during a cutscene or after they're eliminated
+///
+/// A disabled player's buttons all read as released and their stick as
+/// centered, like during [`InputCaptureMode`], while the other player is
+/// unaffected.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{Player, PlayerInputEnabled};
+///
+/// fn eliminate_p1_system(mut enabled: ResMut<PlayerInputEnabled>) {
+///   enabled.set(Player::P1, false);
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerInputEnabled {
+  p1: bool,
+  p2: bool,
+}
+
+impl Default for PlayerInputEnabled {
+  fn default() -> Self {
+    Self { p1: true, p2: true }
+  }
+}
+
+impl PlayerInputEnabled {
+  /// Returns whether the player's input is reported. Defaults to true
+  pub fn enabled(&self, player: Player) -> bool {
+    match player {
+      Player::P1 => self.p1,
+      Player::P2 => self.p2,
+    }
+  }
+
+  /// Turns the player's input on or off
+  pub fn set(&mut self, player: Player, enabled: bool) {
+    match player {
+      Player::P1 => self.p1 = enabled,
+      Player::P2 => self.p2 = enabled,
+    }
+  }
+}
+
+/// Clears the state [`DevcadeControls`](crate::DevcadeControls) keeps
+/// between frames, so a new scene starts with a clean slate
+///
+/// After [`InputReset::reset`], the next time each system reads
+/// `DevcadeControls` nothing is [just pressed](crate::DevcadeControls::just_pressed)
+/// or released, buttons that are still held count as pressed from that
+/// frame on, and earlier presses and [`last_changed`](crate::DevcadeControls::last_changed)
+/// are forgotten. This stops the press that started a scene from also
+/// counting in it.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::InputReset;
+///
+/// fn enter_level_system(mut reset: ResMut<InputReset>) {
+///   reset.reset();
+/// }
+/// ```
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputReset {
+  /// How many times [`InputReset::reset`] has been called
+  generation: u64,
+}
+
+impl InputReset {
+  /// Clears the control state the next time each system reads it
+  pub fn reset(&mut self) {
+    self.generation += 1;
+  }
+
+  pub(crate) fn generation(&self) -> u64 {
+    self.generation
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fake_gamepad::{keyboard_app, press_key, release_key, update_and_read};
+  use crate::{Button, DevcadeInputConfig};
+  use bevy::time::TimeUpdateStrategy;
+  use std::time::Duration;
+
+  #[test]
+  fn capture_hides_presses_from_the_game() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (input.just_pressed(Player::P1, Button::A1), input.captured())
+    });
+    app.insert_resource(InputCaptureMode { active: true });
+    let jumped_and_captured =
+      |app: &mut App| update_and_read::<(bool, Option<(Player, Button)>)>(app);
+
+    press_key(&mut app, KeyCode::Q);
+    assert_eq!(
+      jumped_and_captured(&mut app),
+      (false, Some((Player::P1, Button::A1)))
+    );
+
+    app.world.resource_mut::<InputCaptureMode>().active = false;
+    release_key(&mut app, KeyCode::Q);
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    assert_eq!(jumped_and_captured(&mut app), (true, None));
+  }
+
+  #[test]
+  fn disabled_players_read_as_idle() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      let pressed: Vec<_> = enum_iterator::all::<Player>()
+        .filter(|&player| input.pressed(player, Button::A1))
+        .collect();
+      (pressed, input.active_players(Duration::from_secs(10)))
+    });
+    let mut enabled = PlayerInputEnabled::default();
+    enabled.set(Player::P1, false);
+    app.insert_resource(enabled);
+    let pressed_and_active = |app: &mut App| update_and_read::<(Vec<Player>, Vec<Player>)>(app);
+
+    press_key(&mut app, KeyCode::Q);
+    press_key(&mut app, KeyCode::Y);
+    assert_eq!(
+      pressed_and_active(&mut app),
+      (vec![Player::P2], vec![Player::P2])
+    );
+
+    app
+      .world
+      .resource_mut::<PlayerInputEnabled>()
+      .set(Player::P1, true);
+    assert_eq!(pressed_and_active(&mut app).0, [Player::P1, Player::P2]);
+  }
+
+  #[test]
+  fn reset_forgets_held_buttons() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (
+        input.pressed(Player::P1, Button::A1),
+        input.just_pressed(Player::P1, Button::A1),
+        input.time_since_pressed(Player::P1, Button::A1),
+      )
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    let confirm = |app: &mut App| update_and_read::<(bool, bool, Option<Duration>)>(app);
+
+    press_key(&mut app, KeyCode::Q);
+    confirm(&mut app);
+    assert_eq!(confirm(&mut app).2, Some(Duration::from_millis(100)));
+
+    // The new scene starts while A1 is still held
+    app.world.resource_mut::<InputReset>().reset();
+    assert_eq!(confirm(&mut app), (true, false, Some(Duration::ZERO)));
+    assert_eq!(
+      confirm(&mut app),
+      (true, false, Some(Duration::from_millis(100)))
+    );
+
+    // A fresh press counts
+    release_key(&mut app, KeyCode::Q);
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    assert!(confirm(&mut app).1);
+  }
+
+  #[test]
+  fn paused_input_reads_as_idle() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (
+        input.pressed(Player::P1, Button::A1),
+        input.just_pressed(Player::P1, Button::A1),
+        input.stick(Player::P1),
+        input.active_players(Duration::from_secs(10)),
+      )
+    });
+    app.insert_resource(InputPaused(true));
+    type Frame = (bool, bool, Vec2, Vec<Player>);
+
+    press_key(&mut app, KeyCode::Q);
+    press_key(&mut app, KeyCode::N);
+    for _ in 0..3 {
+      assert_eq!(
+        update_and_read::<Frame>(&mut app),
+        (false, false, Vec2::ZERO, vec![])
+      );
+    }
+
+    // Held through the pause, so it doesn't count as a new press
+    app.world.resource_mut::<InputPaused>().0 = false;
+    let (pressed, just_pressed, stick, _) = update_and_read::<Frame>(&mut app);
+    assert!(pressed && !just_pressed);
+    assert_eq!(stick, Vec2::X);
+
+    // New presses work as usual
+    release_key(&mut app, KeyCode::Q);
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    assert!(update_and_read::<Frame>(&mut app).1);
+  }
+}
@@ -0,0 +1,165 @@
+use crate::{Button, DevcadeControls, Player};
+use bevy::{app::AppExit, prelude::*};
+use std::time::Duration;
+
+/// How a [`ChordEntry`]'s button has to be pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressMode {
+  /// The button is down
+  Pressed,
+  /// The button went down on this frame
+  JustPressed,
+  /// The button has been down for at least this long
+  HeldFor(Duration),
+}
+
+/// One button a [`Chord`] needs, and how it has to be pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChordEntry {
+  /// Player whose button it is
+  pub player: Player,
+  /// The button
+  pub button: Button,
+  /// How the button has to be pressed
+  pub mode: PressMode,
+}
+
+impl ChordEntry {
+  /// Creates an entry for `player` pressing `button` as `mode` describes
+  pub fn new(player: Player, button: Button, mode: PressMode) -> Self {
+    Self {
+      player,
+      button,
+      mode,
+    }
+  }
+
+  /// Returns true if the button is pressed the way this entry needs
+  pub fn satisfied(&self, input: &DevcadeControls) -> bool {
+    match self.mode {
+      PressMode::Pressed => input.pressed(self.player, self.button),
+      PressMode::JustPressed => input.just_pressed(self.player, self.button),
+      PressMode::HeldFor(duration) => {
+        input.pressed(self.player, self.button)
+          && input
+            .time_since_pressed(self.player, self.button)
+            .is_some_and(|held_for| held_for >= duration)
+      }
+    }
+  }
+}
+
+/// A combination of buttons that have to be pressed together
+///
+/// Each entry can ask for a different player, button and [`PressMode`], so
+/// chords don't have to treat both players the same.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Chord {
+  /// Every button in the chord
+  pub entries: Vec<ChordEntry>,
+}
+
+impl Chord {
+  /// Creates a chord out of `entries`
+  pub fn new(entries: impl IntoIterator<Item = ChordEntry>) -> Self {
+    Self {
+      entries: entries.into_iter().collect(),
+    }
+  }
+
+  /// Adds an entry to the chord
+  pub fn with(mut self, player: Player, button: Button, mode: PressMode) -> Self {
+    self.entries.push(ChordEntry::new(player, button, mode));
+    self
+  }
+
+  /// Returns true if every entry is satisfied. A chord with no entries never
+  /// is
+  pub fn satisfied(&self, input: &DevcadeControls) -> bool {
+    !self.entries.is_empty() && self.entries.iter().all(|entry| entry.satisfied(input))
+  }
+}
+
+/// The chord that quits the game for [`exit_on_chord`]
+///
+/// Defaults to both players holding [`Button::Menu`], like
+/// [`exit_on_menu_buttons`](crate::exit_on_menu_buttons).
+/// [`DevcadePlugin`](crate::DevcadePlugin) adds the default one.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ExitChord(pub Chord);
+
+impl Default for ExitChord {
+  fn default() -> Self {
+    Self(Chord::new(enum_iterator::all::<Player>().map(|player| {
+      ChordEntry::new(player, Button::Menu, PressMode::Pressed)
+    })))
+  }
+}
+
+/// Exit the app when the [`ExitChord`] is pressed
+///
+/// # Examples
+/// Quitting when P1 holds Menu and P2 taps it:
+/// ```
+/// use bevy::{app::AppExit, input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+/// use devcaders::{
+///   exit_on_chord, Button, Chord, ExitChord, KeyboardBindings, Player, PressMode,
+/// };
+/// use std::time::Duration;
+///
+/// #[derive(Resource, Default)]
+/// struct Exited(bool);
+///
+/// fn exited(mut exits: EventReader<AppExit>, mut exited: ResMut<Exited>) {
+///   exited.0 = exits.read().count() > 0;
+/// }
+///
+/// let mut bindings = KeyboardBindings::default();
+/// bindings.bind(Player::P2, Button::Menu, KeyCode::Back);
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+///   .insert_resource(bindings)
+///   .insert_resource(ExitChord(
+///     Chord::default()
+///       .with(Player::P1, Button::Menu, PressMode::HeldFor(Duration::from_secs(1)))
+///       .with(Player::P2, Button::Menu, PressMode::JustPressed),
+///   ))
+///   .init_resource::<Exited>()
+///   .add_systems(Update, (exit_on_chord, exited).chain());
+///
+/// let tap_p2 = |app: &mut App| {
+///   app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Back);
+///   app.update();
+///   app.world.resource_mut::<Input<KeyCode>>().release(KeyCode::Back);
+///   app.world.resource::<Exited>().0
+/// };
+///
+/// // P2's tap alone isn't enough
+/// assert!(!tap_p2(&mut app));
+///
+/// // Nor is P2 pressing along with P1 but not letting go until P1 has held
+/// // long enough
+/// let mut input = app.world.resource_mut::<Input<KeyCode>>();
+/// input.press(KeyCode::Escape);
+/// input.press(KeyCode::Back);
+/// for _ in 0..5 {
+///   app.update();
+///   assert!(!app.world.resource::<Exited>().0);
+/// }
+/// app.world.resource_mut::<Input<KeyCode>>().release(KeyCode::Back);
+/// app.update();
+/// assert!(!app.world.resource::<Exited>().0);
+///
+/// assert!(tap_p2(&mut app));
+/// ```
+pub fn exit_on_chord(
+  input: DevcadeControls,
+  chord: Res<ExitChord>,
+  mut exit: EventWriter<AppExit>,
+) {
+  if chord.0.satisfied(&input) {
+    exit.send(AppExit);
+  }
+}
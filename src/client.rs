@@ -1,26 +1,351 @@
-use devcade_onboard_types::{Request, RequestBody, Response, ResponseBody};
-use std::collections::HashMap;
+use devcade_onboard_types::{
+  schema::DevcadeGame, Map, Player, Request, RequestBody, Response, ResponseBody, Value,
+};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::UnixStream;
-use tokio::sync::{mpsc, oneshot, Mutex, OnceCell};
+use std::time::{Duration, Instant};
+use tokio::io::{self, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::sync::{mpsc, oneshot, Mutex};
 
 pub struct BackendClient {
-  connection: OnceCell<SynchronizedConnection>,
+  address: BackendAddress,
+  connection: Mutex<Option<Arc<SynchronizedConnection>>>,
+  on_complete: Option<CompletionHook>,
+  log_level: log::Level,
+  /// Throttles the messages [`BackendClient::send_with_retry`] logs before
+  /// each retry
+  retry_log: std::sync::Mutex<LogThrottle>,
+  /// Requests sent that haven't finished yet
+  pending: AtomicUsize,
+  rate_limiter: Option<RateLimiter>,
+  lifecycle: std::sync::Mutex<ConnectionLifecycle>,
+}
+
+/// What [`BackendClient::stats`] reports about the connection
+#[derive(Default)]
+struct ConnectionLifecycle {
+  /// When the latest connection was made, and its closed flag
+  current: Option<(Instant, Arc<AtomicBool>)>,
+  /// How many connections have been made
+  connections: u64,
+}
+
+/// A snapshot of how a [`BackendClient`] is doing, from
+/// [`BackendClient::stats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientStats {
+  /// Requests sent that are still waiting for a response, as in
+  /// [`BackendClient::pending_requests`]
+  pub pending_requests: usize,
+  /// When the current connection was made, or `None` if there isn't one
+  pub connected_since: Option<Instant>,
+  /// How many times the client has connected again after its first
+  /// connection. A number that keeps climbing means the backend keeps
+  /// dropping the connection
+  pub reconnect_count: u64,
+}
+
+/// Counts a request as pending for as long as it's alive, including when
+/// it's dropped before finishing
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> PendingGuard<'a> {
+  fn new(pending: &'a AtomicUsize) -> Self {
+    pending.fetch_add(1, Ordering::Relaxed);
+    Self(pending)
+  }
+}
+
+impl Drop for PendingGuard<'_> {
+  fn drop(&mut self) {
+    self.0.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// Where [`BackendClient`] connects to the backend
+#[derive(Debug, Clone)]
+enum BackendAddress {
+  /// A Unix socket, which is how the backend listens on the cabinet
+  Unix(PathBuf),
+  /// A `host:port`, for reaching a cabinet's backend forwarded over the
+  /// network
+  Tcp(String),
+}
+
+/// Called by [`BackendClient`] after every request, with how long it took
+pub type CompletionHook =
+  Arc<dyn Fn(&RequestBody, Result<&ResponseBody, &RequestError>, Duration) + Send + Sync>;
+
+/// How often [`LogThrottle`] lets the same message through
+const LOG_THROTTLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Logs a repetitive message at most once every [`LOG_THROTTLE_INTERVAL`],
+/// counting how many times it was skipped in between
+struct LogThrottle {
+  level: log::Level,
+  last_logged: Option<Instant>,
+  suppressed: u32,
+}
+
+impl LogThrottle {
+  fn new(level: log::Level) -> Self {
+    Self {
+      level,
+      last_logged: None,
+      suppressed: 0,
+    }
+  }
+
+  fn log(&mut self, args: fmt::Arguments) {
+    let now = Instant::now();
+    if self
+      .last_logged
+      .is_some_and(|last_logged| now - last_logged < LOG_THROTTLE_INTERVAL)
+    {
+      self.suppressed += 1;
+      return;
+    }
+    if self.suppressed > 0 {
+      log::log!(
+        self.level,
+        "{args} ({} similar messages suppressed)",
+        self.suppressed
+      );
+    } else {
+      log::log!(self.level, "{args}");
+    }
+    self.last_logged = Some(now);
+    self.suppressed = 0;
+  }
 }
 
 type RequestSender = oneshot::Sender<Result<ResponseBody, RequestError>>;
 struct SynchronizedConnection {
   requests_tx: mpsc::Sender<(RequestBody, RequestSender)>,
+  listeners: Arc<Mutex<Listeners>>,
+  /// Set once either side of the socket has gone away
+  closed: Arc<AtomicBool>,
+}
+
+impl SynchronizedConnection {
+  fn is_closed(&self) -> bool {
+    self.closed.load(Ordering::Acquire) || self.requests_tx.is_closed()
+  }
+}
+
+/// Requests sent on a connection that haven't been answered yet
+#[derive(Default)]
+struct Listeners {
+  /// Requests waiting on a response, by ID
+  waiting: HashMap<u32, RequestSender>,
+  /// Requests that were cancelled, so their responses can be quietly ignored
+  cancelled: HashSet<u32>,
+}
+
+impl Listeners {
+  fn contains(&self, request_id: u32) -> bool {
+    self.waiting.contains_key(&request_id) || self.cancelled.contains(&request_id)
+  }
+
+  /// Stops waiting on requests whose receivers have been dropped
+  fn remove_cancelled(&mut self) {
+    let cancelled = &mut self.cancelled;
+    self.waiting.retain(|&request_id, callback_tx| {
+      let is_cancelled = callback_tx.is_closed();
+      if is_cancelled {
+        cancelled.insert(request_id);
+      }
+      !is_cancelled
+    });
+  }
+}
+
+/// Waits for the response to one request. Dropping it before the response
+/// arrives cancels the request
+struct ResponseReceiver {
+  rx: oneshot::Receiver<Result<ResponseBody, RequestError>>,
+  listeners: Arc<Mutex<Listeners>>,
+}
+
+impl Drop for ResponseReceiver {
+  fn drop(&mut self) {
+    self.rx.close();
+    // If the lock is busy, the writer clears it out before its next request
+    if let Ok(mut listeners) = self.listeners.try_lock() {
+      listeners.remove_cancelled();
+    }
+  }
+}
+
+/// How [`BackendClient::send_with_retry`] retries failed requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+  /// Most times a request will be sent, including the first attempt
+  pub max_attempts: u32,
+  /// How long to wait before the first retry. Doubles after every retry
+  pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      backoff: Duration::from_millis(100),
+    }
+  }
+}
+
+/// One time limit shared by several requests, for multi-step flows like
+/// signing in that should finish within a set time overall
+///
+/// Pass it to [`BackendClient::send_before`] for each request. Each one gets
+/// whatever time is left, and once it's used up requests fail with
+/// [`RequestError::Timeout`] without being sent. The timeout reports the
+/// whole budget as the time waited.
+///
+/// # Example
+/// A sign in that has 2 seconds overall to read the tag and look up its user:
+/// ```no_run
+/// use devcaders::devcade_onboard_types::{Player, RequestBody};
+/// use devcaders::{BackendClient, Deadline, ResponseBodyExt};
+/// use std::time::Duration;
+///
+/// let client = BackendClient::default();
+/// let deadline = Deadline::after(Duration::from_secs(2));
+/// let user = futures_lite::future::block_on(async_compat::Compat::new(async {
+///   let tag = client.send_before(RequestBody::GetNfcTag(Player::P1), &deadline).await?;
+///   let Some(tag) = tag.expect_nfc_tag()? else {
+///     return Ok(None);
+///   };
+///   let user = client.send_before(RequestBody::GetNfcUser(tag), &deadline).await?;
+///   user.expect_nfc_user().map(Some)
+/// }));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+  started: Instant,
+  budget: Duration,
+}
+
+impl Deadline {
+  /// Starts a deadline that expires `budget` from now
+  pub fn after(budget: Duration) -> Self {
+    Self {
+      started: Instant::now(),
+      budget,
+    }
+  }
+
+  /// How much time is left, or zero once it's expired
+  pub fn remaining(&self) -> Duration {
+    self.budget.saturating_sub(self.started.elapsed())
+  }
+
+  /// Whether the time has run out
+  pub fn expired(&self) -> bool {
+    self.remaining().is_zero()
+  }
 }
 
+/// What [`BackendClient::rate_limit`] does with a request sent after the
+/// limit has been used up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitMode {
+  /// Hold the request until the limit allows it
+  Wait,
+  /// Fail the request with [`RequestError::RateLimited`] straight away
+  Fail,
+}
+
+/// Token bucket behind [`BackendClient::rate_limit`]
+struct RateLimiter {
+  per_second: f64,
+  mode: RateLimitMode,
+  /// Requests that can be sent right now, which goes negative while requests
+  /// are waiting their turn, and when it was last topped up
+  bucket: std::sync::Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+  /// Takes a request's worth out of the bucket, waiting for it to refill if
+  /// it's empty
+  async fn acquire(&self) -> Result<(), RequestError> {
+    let wait = {
+      let mut bucket = self.bucket.lock().unwrap_or_else(|err| err.into_inner());
+      let (tokens, refilled) = &mut *bucket;
+      let now = Instant::now();
+      *tokens = (*tokens + (now - *refilled).as_secs_f64() * self.per_second).min(self.per_second);
+      *refilled = now;
+      if *tokens < 1.0 && self.mode == RateLimitMode::Fail {
+        return Err(RequestError::RateLimited);
+      }
+      *tokens -= 1.0;
+      Duration::from_secs_f64((-*tokens).max(0.0) / self.per_second)
+    };
+    if !wait.is_zero() {
+      tokio::time::sleep(wait).await;
+    }
+    Ok(())
+  }
+}
+
+/// Why a request to the backend failed
+///
+/// # Example
+/// Not having a backend at all is reported separately from other
+/// connection problems:
+/// ```
+/// use devcaders::{BackendClient, RequestError};
+/// use std::os::unix::net::UnixListener;
+///
+/// let missing = std::env::temp_dir().join("devcaders-unavailable.sock");
+/// let _ = std::fs::remove_file(&missing);
+/// let client = BackendClient::with_path(&missing);
+/// let result = futures_lite::future::block_on(async_compat::Compat::new(client.ping()));
+/// assert!(matches!(result, Err(RequestError::BackendUnavailable(path)) if path == missing));
+///
+/// // A socket nobody is listening on any more
+/// let stale = std::env::temp_dir().join("devcaders-stale.sock");
+/// let _ = std::fs::remove_file(&stale);
+/// drop(UnixListener::bind(&stale).unwrap());
+/// let client = BackendClient::with_path(&stale);
+/// let result = futures_lite::future::block_on(async_compat::Compat::new(client.ping()));
+/// assert!(matches!(result, Err(RequestError::IoError(_))));
+/// ```
 #[derive(Debug)]
 pub enum RequestError {
   IoError(io::Error),
+  /// There's no backend socket at the path, e.g. because the game isn't
+  /// running on a cabinet
+  BackendUnavailable(PathBuf),
   ResponseError(String),
-  UnexpectedResponse(ResponseBody),
+  /// The backend answered with a different kind of response than the
+  /// request calls for
+  UnexpectedResponse {
+    /// Name of the [`ResponseBody`] variant the request calls for
+    expected: &'static str,
+    /// What the backend answered with
+    actual: Box<ResponseBody>,
+  },
   ChannelClosed,
+  /// The backend didn't answer in time
+  Timeout {
+    /// How long the client waited for an answer
+    waited: Duration,
+    /// The request that went unanswered
+    request: Box<RequestBody>,
+  },
+  /// The request couldn't be encoded to send to the backend
+  SerializeError(serde_json::Error),
+  /// The request was cancelled before the backend answered
+  Cancelled,
+  /// The client's [rate limit](BackendClient::rate_limit) was used up, so
+  /// the request wasn't sent
+  RateLimited,
 }
 
 impl fmt::Display for RequestError {
@@ -28,8 +353,19 @@ impl fmt::Display for RequestError {
     match self {
       Self::IoError(err) => write!(f, "IoError({err})"),
       Self::ResponseError(err) => write!(f, "ResponseError({err})"),
-      Self::UnexpectedResponse(response) => write!(f, "UnexpectedResponse({response})"),
+      Self::UnexpectedResponse { expected, actual } => write!(
+        f,
+        "UnexpectedResponse(expected {expected}, got {}: {actual})",
+        response_variant(actual)
+      ),
+      Self::BackendUnavailable(path) => write!(f, "BackendUnavailable(no socket at {path:?})"),
       Self::ChannelClosed => write!(f, "ChannelClosed"),
+      Self::Timeout { waited, request } => {
+        write!(f, "Timeout(no answer to {request} after {waited:?})")
+      }
+      Self::SerializeError(err) => write!(f, "SerializeError({err})"),
+      Self::Cancelled => write!(f, "Cancelled"),
+      Self::RateLimited => write!(f, "RateLimited"),
     }
   }
 }
@@ -40,11 +376,398 @@ impl From<io::Error> for RequestError {
   }
 }
 
+impl RequestError {
+  /// A short message to show players, unlike [`Display`](fmt::Display)
+  /// which is meant for developers
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::devcade_onboard_types::{RequestBody, ResponseBody};
+  /// use devcaders::RequestError;
+  /// use std::io;
+  /// use std::time::Duration;
+  ///
+  /// let errors = [
+  ///   RequestError::IoError(io::ErrorKind::BrokenPipe.into()),
+  ///   RequestError::BackendUnavailable("/tmp/onboard.sock".into()),
+  ///   RequestError::ResponseError("No such tag".to_owned()),
+  ///   RequestError::UnexpectedResponse {
+  ///     expected: "NfcTag",
+  ///     actual: Box::new(ResponseBody::Pong),
+  ///   },
+  ///   RequestError::ChannelClosed,
+  ///   RequestError::Timeout {
+  ///     waited: Duration::from_secs(1),
+  ///     request: Box::new(RequestBody::Ping),
+  ///   },
+  ///   RequestError::SerializeError(serde_json::from_str::<u8>("").unwrap_err()),
+  ///   RequestError::Cancelled,
+  ///   RequestError::RateLimited,
+  /// ];
+  /// let messages = errors.map(|err| err.user_message());
+  /// assert_eq!(
+  ///   messages,
+  ///   [
+  ///     "Connection lost",
+  ///     "Backend offline",
+  ///     "Request failed",
+  ///     "Something went wrong",
+  ///     "Connection lost",
+  ///     "Timed out",
+  ///     "Something went wrong",
+  ///     "Cancelled",
+  ///     "Busy, try again",
+  ///   ]
+  /// );
+  /// ```
+  pub fn user_message(&self) -> &'static str {
+    match self {
+      Self::IoError(_) | Self::ChannelClosed => "Connection lost",
+      Self::BackendUnavailable(_) => "Backend offline",
+      Self::ResponseError(_) => "Request failed",
+      Self::UnexpectedResponse { .. } | Self::SerializeError(_) => "Something went wrong",
+      Self::Timeout { .. } => "Timed out",
+      Self::Cancelled => "Cancelled",
+      Self::RateLimited => "Busy, try again",
+    }
+  }
+
+  /// Whether the request might succeed if it's sent again
+  fn is_transient(&self) -> bool {
+    matches!(
+      self,
+      Self::IoError(_) | Self::ChannelClosed | Self::Timeout { .. } | Self::RateLimited
+    )
+  }
+}
+
+/// Picks which request to send, from [`BackendClient::request`]
+pub struct RequestBuilder<'a> {
+  client: &'a BackendClient,
+}
+
+impl<'a> RequestBuilder<'a> {
+  /// Checks that the backend is alive
+  pub fn ping(self) -> PendingRequest<'a> {
+    self.body(RequestBody::Ping)
+  }
+
+  /// Asks for every game installed on the cabinet
+  pub fn game_list(self) -> PendingRequest<'a> {
+    self.body(RequestBody::GetGameList)
+  }
+
+  /// Asks for the association id of the tag on `reader`
+  pub fn nfc_tag(self, reader: Player) -> PendingRequest<'a> {
+    self.body(RequestBody::GetNfcTag(reader))
+  }
+
+  /// Asks for the user a tag's association id belongs to
+  pub fn nfc_user(self, association_id: impl Into<String>) -> PendingRequest<'a> {
+    self.body(RequestBody::GetNfcUser(association_id.into()))
+  }
+
+  /// Sends any other request
+  pub fn body(self, body: RequestBody) -> PendingRequest<'a> {
+    PendingRequest {
+      client: self.client,
+      body,
+    }
+  }
+}
+
+/// A request ready to be sent, from [`RequestBuilder`]
+pub struct PendingRequest<'a> {
+  client: &'a BackendClient,
+  body: RequestBody,
+}
+
+impl PendingRequest<'_> {
+  /// The request that will be sent
+  pub fn body(&self) -> &RequestBody {
+    &self.body
+  }
+
+  /// Sends the request with [`BackendClient::send`]
+  pub async fn send(self) -> Result<ResponseBody, RequestError> {
+    self.client.send(self.body).await
+  }
+}
+
+/// The backend's answer to [`BackendClient::ping`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pong;
+
+/// Pulls the expected payload out of a [`ResponseBody`]
+///
+/// Each method returns [`RequestError::UnexpectedResponse`] if the backend
+/// answered with a different variant, or [`RequestError::ResponseError`] for
+/// [`ResponseBody::Err`].
+///
+/// # Example
+/// ```
+/// use devcaders::devcade_onboard_types::ResponseBody;
+/// use devcaders::{RequestError, ResponseBodyExt};
+///
+/// let tag = ResponseBody::NfcTag(Some("abc".to_owned())).expect_nfc_tag();
+/// assert_eq!(tag.unwrap().as_deref(), Some("abc"));
+///
+/// let err = ResponseBody::Pong.expect_nfc_tag().unwrap_err();
+/// assert!(matches!(&err, RequestError::UnexpectedResponse { expected: "NfcTag", actual } if matches!(**actual, ResponseBody::Pong)));
+/// assert_eq!(err.to_string(), "UnexpectedResponse(expected NfcTag, got Pong: Pong)");
+///
+/// let user = ResponseBody::Err("No such tag".to_owned()).expect_nfc_user();
+/// assert!(matches!(user, Err(RequestError::ResponseError(err)) if err == "No such tag"));
+/// ```
+pub trait ResponseBodyExt {
+  /// Expects [`ResponseBody::Pong`]
+  fn expect_pong(self) -> Result<Pong, RequestError>;
+  /// Expects [`ResponseBody::NfcTag`], returning the tag's association id
+  fn expect_nfc_tag(self) -> Result<Option<String>, RequestError>;
+  /// Expects [`ResponseBody::NfcUser`], returning the user's attributes
+  fn expect_nfc_user(self) -> Result<Map<String, Value>, RequestError>;
+  /// Expects [`ResponseBody::GameList`], returning the games
+  fn expect_game_list(self) -> Result<Vec<DevcadeGame>, RequestError>;
+}
+
+impl ResponseBodyExt for ResponseBody {
+  fn expect_pong(self) -> Result<Pong, RequestError> {
+    match self {
+      ResponseBody::Pong => Ok(Pong),
+      body => Err(unexpected("Pong", body)),
+    }
+  }
+
+  fn expect_nfc_tag(self) -> Result<Option<String>, RequestError> {
+    match self {
+      ResponseBody::NfcTag(tag_id) => Ok(tag_id),
+      body => Err(unexpected("NfcTag", body)),
+    }
+  }
+
+  fn expect_nfc_user(self) -> Result<Map<String, Value>, RequestError> {
+    match self {
+      ResponseBody::NfcUser(user) => Ok(user),
+      body => Err(unexpected("NfcUser", body)),
+    }
+  }
+
+  fn expect_game_list(self) -> Result<Vec<DevcadeGame>, RequestError> {
+    match self {
+      ResponseBody::GameList(games) => Ok(games),
+      body => Err(unexpected("GameList", body)),
+    }
+  }
+}
+
+/// Something that can be sent to the backend with
+/// [`BackendClient::request_typed`]
+///
+/// Implement this for your own request types to add requests without
+/// needing a dedicated method on [`BackendClient`].
+pub trait IntoRequestBody {
+  /// Turns this into the request to send
+  fn into_request_body(self) -> RequestBody;
+}
+
+impl IntoRequestBody for RequestBody {
+  fn into_request_body(self) -> RequestBody {
+    self
+  }
+}
+
+/// Something that can be pulled out of the backend's answer by
+/// [`BackendClient::request_typed`]
+///
+/// Like [`ResponseBodyExt`], implementations return
+/// [`RequestError::UnexpectedResponse`] if the answer is a different variant.
+pub trait FromResponseBody: Sized {
+  /// Pulls `Self` out of `body`
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError>;
+}
+
+impl FromResponseBody for ResponseBody {
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+    Ok(body)
+  }
+}
+
+impl FromResponseBody for Pong {
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+    body.expect_pong()
+  }
+}
+
+/// The tag's association id, from [`ResponseBody::NfcTag`]
+impl FromResponseBody for Option<String> {
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+    body.expect_nfc_tag()
+  }
+}
+
+/// The user's attributes, from [`ResponseBody::NfcUser`]
+impl FromResponseBody for Map<String, Value> {
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+    body.expect_nfc_user()
+  }
+}
+
+impl FromResponseBody for Vec<DevcadeGame> {
+  fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+    body.expect_game_list()
+  }
+}
+
+/// The error for getting `body` when the `expected` variant was called for
+fn unexpected(expected: &'static str, body: ResponseBody) -> RequestError {
+  match body {
+    ResponseBody::Err(err) => RequestError::ResponseError(err),
+    body => RequestError::UnexpectedResponse {
+      expected,
+      actual: Box::new(body),
+    },
+  }
+}
+
+/// Returns the name of the [`ResponseBody`] variant the backend answers
+/// `request` with when it succeeds. Any request can also be answered with
+/// [`ResponseBody::Err`]
+///
+/// # Example
+/// ```
+/// use devcaders::devcade_onboard_types::{Player, RequestBody};
+/// use devcaders::expected_response;
+///
+/// assert_eq!(expected_response(&RequestBody::Ping), "Pong");
+/// assert_eq!(expected_response(&RequestBody::GetNfcTag(Player::P1)), "NfcTag");
+/// ```
+pub fn expected_response(request: &RequestBody) -> &'static str {
+  match request {
+    RequestBody::Ping => "Pong",
+    RequestBody::GetGameList
+    | RequestBody::GetGameListFromFs
+    | RequestBody::GetGameListFromTag(_) => "GameList",
+    RequestBody::GetGame(_) => "Game",
+    RequestBody::GetTagList => "TagList",
+    RequestBody::GetTag(_) => "Tag",
+    RequestBody::GetUser(_) => "User",
+    RequestBody::GetNfcTag(_) => "NfcTag",
+    RequestBody::GetNfcUser(_) => "NfcUser",
+    RequestBody::DownloadGame(_)
+    | RequestBody::DownloadIcon(_)
+    | RequestBody::DownloadBanner(_)
+    | RequestBody::SetProduction(_)
+    | RequestBody::LaunchGame(_) => "Ok",
+  }
+}
+
+/// Returns every request the client knows how to send, with placeholder
+/// arguments, alongside the name of the [`ResponseBody`] variant the backend
+/// answers it with (see [`expected_response`])
+///
+/// Handy for self-test screens and tooling that exercise the backend. Not
+/// every request is harmless: [`RequestBody::LaunchGame`] and the downloads
+/// act on the cabinet, so check what a request does before sending it.
+///
+/// Unlike [`RequestBody::variants`], this includes every variant.
+///
+/// # Example
+/// ```
+/// use devcaders::devcade_onboard_types::RequestBody;
+/// use devcaders::{expected_response, supported_requests};
+///
+/// let requests = supported_requests();
+/// assert_eq!(requests.len(), 15);
+/// assert!(matches!(requests[0], (RequestBody::Ping, "Pong")));
+/// assert!(requests
+///   .iter()
+///   .any(|(request, _)| matches!(request, RequestBody::GetNfcTag(_))));
+/// for (request, expected) in &requests {
+///   assert_eq!(expected_response(request), *expected);
+/// }
+/// ```
+pub fn supported_requests() -> Vec<(RequestBody, &'static str)> {
+  [
+    RequestBody::Ping,
+    RequestBody::GetGameList,
+    RequestBody::GetGameListFromFs,
+    RequestBody::GetGame(String::new()),
+    RequestBody::DownloadGame(String::new()),
+    RequestBody::DownloadIcon(String::new()),
+    RequestBody::DownloadBanner(String::new()),
+    RequestBody::GetTagList,
+    RequestBody::GetTag(String::new()),
+    RequestBody::GetGameListFromTag(String::new()),
+    RequestBody::GetUser(String::new()),
+    RequestBody::SetProduction(false),
+    RequestBody::LaunchGame(String::new()),
+    RequestBody::GetNfcTag(Player::P1),
+    RequestBody::GetNfcUser(String::new()),
+  ]
+  .into_iter()
+  .map(|request| {
+    let expected = expected_response(&request);
+    (request, expected)
+  })
+  .collect()
+}
+
+/// Name of `body`'s variant, for error messages
+fn response_variant(body: &ResponseBody) -> &'static str {
+  match body {
+    ResponseBody::Pong => "Pong",
+    ResponseBody::Ok => "Ok",
+    ResponseBody::Err(_) => "Err",
+    ResponseBody::GameList(_) => "GameList",
+    ResponseBody::Game(_) => "Game",
+    ResponseBody::TagList(_) => "TagList",
+    ResponseBody::Tag(_) => "Tag",
+    ResponseBody::User(_) => "User",
+    ResponseBody::NfcTag(_) => "NfcTag",
+    ResponseBody::NfcUser(_) => "NfcUser",
+    ResponseBody::InternalGame(_) => "InternalGame",
+  }
+}
+
+/// Whether sending `body` more than once has the same effect as sending it once
+fn is_idempotent(body: &RequestBody) -> bool {
+  match body {
+    RequestBody::Ping
+    | RequestBody::GetGameList
+    | RequestBody::GetGameListFromFs
+    | RequestBody::GetGame(_)
+    | RequestBody::GetTagList
+    | RequestBody::GetTag(_)
+    | RequestBody::GetGameListFromTag(_)
+    | RequestBody::GetUser(_)
+    | RequestBody::GetNfcTag(_)
+    | RequestBody::GetNfcUser(_) => true,
+    RequestBody::DownloadGame(_)
+    | RequestBody::DownloadIcon(_)
+    | RequestBody::DownloadBanner(_)
+    | RequestBody::SetProduction(_)
+    | RequestBody::LaunchGame(_) => false,
+  }
+}
+
+/// Frame buffers bigger than this are shrunk back down after use, so one huge
+/// response doesn't keep its memory around for the life of the connection
+const RETAINED_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Empties `buffer`, giving back memory left over from an unusually big frame
+fn reset_buffer(buffer: &mut Vec<u8>) {
+  buffer.clear();
+  buffer.shrink_to(RETAINED_BUFFER_CAPACITY);
+}
+
 impl Default for BackendClient {
   fn default() -> Self {
-    Self {
-      connection: OnceCell::new(),
+    if let Ok(address) = std::env::var("DEVCADE_ONBOARD_TCP") {
+      return Self::with_tcp(address);
     }
+    Self::with_path(
+      std::env::var("DEVCADE_ONBOARD_PATH").unwrap_or("/tmp/devcade/game.sock".to_owned()),
+    )
   }
 }
 
@@ -55,105 +778,981 @@ impl Default for BackendClient {
 /// try not to make more than one.
 ///
 /// # Example
-/// ```
+/// ```no_run
+/// # use devcaders::{devcade_onboard_types::RequestBody, BackendClient};
+/// # async fn ping() {
 /// let backend_client: BackendClient = Default::default();
-/// let pong = backend_client.send(RequestBody::Ping).await.unwrap();
-/// println!("Pong! {pong}");
+/// let games = backend_client.send(RequestBody::GetGameList).await.unwrap();
+/// println!("Games: {games}");
+/// # }
 /// ```
 impl BackendClient {
-  async fn create_connection() -> Result<SynchronizedConnection, io::Error> {
-    let (connection_reader, mut connection_writer) = UnixStream::connect(
-      std::env::var("DEVCADE_ONBOARD_PATH").unwrap_or("/tmp/devcade/game.sock".to_owned()),
-    )
-    .await?
-    .into_split();
+  /// Creates a client for the backend listening on the Unix socket at `path`.
+  ///
+  /// [`BackendClient::default`] uses the path in the `DEVCADE_ONBOARD_PATH`
+  /// environment variable, or the cabinet's socket if it isn't set.
+  pub fn with_path(path: impl Into<PathBuf>) -> Self {
+    Self::with_address(BackendAddress::Unix(path.into()))
+  }
+
+  /// Creates a client for a backend listening on TCP at `address`, a
+  /// `host:port`. Handy for developing against a cabinet whose socket is
+  /// forwarded over SSH. Requests and responses are exchanged exactly like
+  /// over the Unix socket.
+  ///
+  /// [`BackendClient::default`] uses this when the `DEVCADE_ONBOARD_TCP`
+  /// environment variable is set, in place of `DEVCADE_ONBOARD_PATH`.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::with_tcp("127.0.0.1:9000");
+  /// let pong = futures_lite::future::block_on(async_compat::Compat::new(client.ping()));
+  /// assert!(pong.is_ok());
+  /// ```
+  pub fn with_tcp(address: impl Into<String>) -> Self {
+    Self::with_address(BackendAddress::Tcp(address.into()))
+  }
+
+  fn with_address(address: BackendAddress) -> Self {
+    Self {
+      address,
+      connection: Mutex::new(None),
+      on_complete: None,
+      log_level: log::Level::Error,
+      retry_log: std::sync::Mutex::new(LogThrottle::new(log::Level::Error)),
+      pending: AtomicUsize::new(0),
+      rate_limiter: None,
+      lifecycle: Default::default(),
+    }
+  }
+
+  /// Calls `hook` whenever a request finishes, with the request, its result,
+  /// and how long it took. Useful for feeding request counts, latencies and
+  /// error rates into your own metrics.
+  ///
+  /// Each attempt made by [`BackendClient::send_with_retry`] counts as its
+  /// own request. Requests that time out, like with
+  /// [`BackendClient::send_timeout`], are reported with their
+  /// [`RequestError::Timeout`].
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::default().on_complete(|request, result, duration| {
+  ///   let outcome = if result.is_ok() { "answered" } else { "failed" };
+  ///   println!("{request} {outcome} after {duration:?}");
+  /// });
+  /// ```
+  pub fn on_complete(
+    mut self,
+    hook: impl Fn(&RequestBody, Result<&ResponseBody, &RequestError>, Duration) + Send + Sync + 'static,
+  ) -> Self {
+    self.on_complete = Some(Arc::new(hook));
+    self
+  }
+
+  /// Sets the level the client's background tasks log at when something goes
+  /// wrong on the connection, like a response that can't be decoded or one
+  /// nobody is waiting for, and that [`BackendClient::send_with_retry`] logs
+  /// retries at. Defaults to [`log::Level::Error`].
+  ///
+  /// Repeats of the same message are only logged once every 10 seconds, with
+  /// a count of how many were skipped, so a misbehaving backend can't flood
+  /// the log.
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::BackendClient;
+  ///
+  /// // Connection problems are expected while developing away from a cabinet
+  /// let client = BackendClient::default().log_level(log::Level::Debug);
+  /// ```
+  pub fn log_level(mut self, level: log::Level) -> Self {
+    self.log_level = level;
+    self.retry_log = std::sync::Mutex::new(LogThrottle::new(level));
+    self
+  }
+
+  /// Limits the client to sending `per_second` requests a second, so a
+  /// misbehaving game can't flood the backend. Off by default.
+  ///
+  /// Up to `per_second` requests can be sent at once after the client has
+  /// been idle, and the allowance refills at a steady rate. What happens to
+  /// requests beyond it depends on `mode`. Each attempt made by
+  /// [`BackendClient::send_with_retry`] counts as its own request, and
+  /// [`RequestError::RateLimited`] is retried like other transient errors.
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::{BackendClient, RateLimitMode};
+  ///
+  /// let client = BackendClient::default().rate_limit(10, RateLimitMode::Wait);
+  /// ```
+  pub fn rate_limit(mut self, per_second: u32, mode: RateLimitMode) -> Self {
+    let per_second = f64::from(per_second.max(1));
+    self.rate_limiter = Some(RateLimiter {
+      per_second,
+      mode,
+      bucket: std::sync::Mutex::new((per_second, Instant::now())),
+    });
+    self
+  }
+
+  async fn create_connection(
+    address: &BackendAddress,
+    log_level: log::Level,
+  ) -> Result<SynchronizedConnection, io::Error> {
+    let (connection_reader, mut connection_writer): (
+      Box<dyn AsyncRead + Send + Unpin>,
+      Box<dyn AsyncWrite + Send + Unpin>,
+    ) = match address {
+      BackendAddress::Unix(path) => {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+        (Box::new(reader), Box::new(writer))
+      }
+      BackendAddress::Tcp(address) => {
+        let stream = TcpStream::connect(address).await?;
+        // Requests are small and latency matters more than throughput
+        stream.set_nodelay(true)?;
+        let (reader, writer) = stream.into_split();
+        (Box::new(reader), Box::new(writer))
+      }
+    };
     let (requests_tx, mut requests_rx) = mpsc::channel::<(RequestBody, RequestSender)>(100);
-    let listeners = Arc::new(Mutex::new(HashMap::<u32, RequestSender>::new()));
+    let listeners = Arc::new(Mutex::new(Listeners::default()));
+    let closed = Arc::new(AtomicBool::new(false));
     {
-      let listeners = listeners.clone();
+      let listeners_lock = listeners.clone();
+      let closed = closed.clone();
       tokio::spawn(async move {
         let mut request_id_counter = 0;
+        let mut frame = Vec::new();
         while let Some((body, callback_tx)) = requests_rx.recv().await {
-          let mut listeners = listeners.lock().await;
-          while listeners.contains_key(&request_id_counter) {
+          let mut listeners = listeners_lock.lock().await;
+          if closed.load(Ordering::Acquire) {
+            let _ = callback_tx.send(Err(RequestError::ChannelClosed));
+            return;
+          }
+          listeners.remove_cancelled();
+          if callback_tx.is_closed() {
+            continue;
+          }
+          while listeners.contains(request_id_counter) {
             request_id_counter = request_id_counter.wrapping_add(1);
           }
           let request_id = request_id_counter;
           let request = Request { request_id, body };
 
-          let mut frame = serde_json::to_vec(&request).expect("Couldn't serialize RequestBody?");
+          reset_buffer(&mut frame);
+          if let Err(err) = serde_json::to_writer(&mut frame, &request) {
+            // Only this request is bad, so the connection can keep going
+            let _ = callback_tx.send(Err(RequestError::SerializeError(err)));
+            continue;
+          }
           frame.push(b'\n');
+          // Listen before writing so the response can't beat us to it, but
+          // don't hold the lock while writing. A slow write would otherwise
+          // hold up responses to every other request
+          listeners.waiting.insert(request_id, callback_tx);
+          std::mem::drop(listeners);
           if let Err(err) = connection_writer.write_all(&frame).await {
-            if let Err(Err(err)) = callback_tx.send(Err(err.into())) {
-              log::error!("Couldn't send message to callback! Message we were asked to send was: {request:?}. Failed because {err}");
+            closed.store(true, Ordering::Release);
+            // The reader may have already given up on it
+            if let Some(callback_tx) = listeners_lock.lock().await.waiting.remove(&request_id) {
+              if let Err(Err(err)) = callback_tx.send(Err(err.into())) {
+                log::log!(log_level, "Couldn't send message to callback! Message we were asked to send was: {request:?}. Failed because {err}");
+              }
             }
             return;
           }
-          listeners.insert(request_id, callback_tx);
         }
       });
     }
+    let reader_closed = closed.clone();
+    let reader_listeners = listeners.clone();
     tokio::spawn(async move {
-      let connection_reader = BufReader::new(connection_reader);
-      let mut lines = connection_reader.lines();
-      while let Ok(Some(line)) = lines.next_line().await {
-        let response: Response = match serde_json::from_str(&line) {
+      let mut connection_reader = BufReader::new(connection_reader);
+      let mut line = Vec::new();
+      let mut decode_errors = LogThrottle::new(log_level);
+      let mut unexpected_responses = LogThrottle::new(log_level);
+      loop {
+        reset_buffer(&mut line);
+        match connection_reader.read_until(b'\n', &mut line).await {
+          Ok(0) | Err(_) => break,
+          Ok(_) => {}
+        }
+        let response: Response = match serde_json::from_slice(&line) {
           Ok(response) => response,
           Err(err) => {
-            log::error!("Couldn't decode response ({line}) {err}");
+            let line = String::from_utf8_lossy(&line);
+            decode_errors.log(format_args!(
+              "Couldn't decode response ({}) {err}",
+              line.trim_end()
+            ));
             continue;
           }
         };
 
         let request_id = &response.request_id;
-        let mut listeners = listeners.lock().await;
-        let handler = match listeners.remove(request_id) {
+        let mut listeners = reader_listeners.lock().await;
+        let handler = match listeners.waiting.remove(request_id) {
           Some(handler) => handler,
+          None if listeners.cancelled.remove(request_id) => continue,
           None => {
-            log::error!(
+            unexpected_responses.log(format_args!(
               "Got response for request ID {request_id} that we weren't expecting! {response}"
-            );
+            ));
             continue;
           }
         };
         std::mem::drop(listeners);
 
-        if handler
-          .send(match response.body {
-            ResponseBody::Err(err) => Err(RequestError::ResponseError(err)),
-            body => Ok(body),
-          })
-          .is_err()
-        {
-          log::error!("Failed to send response for {request_id} because the other side of the callback closed");
-        }
+        // The request may have been cancelled since the listeners were
+        // last cleaned up, in which case nobody wants the response
+        let _ = handler.send(match response.body {
+          ResponseBody::Err(err) => Err(RequestError::ResponseError(err)),
+          body => Ok(body),
+        });
       }
+      // Nothing else is coming, so fail everything still waiting on a response
+      let mut listeners = reader_listeners.lock().await;
+      reader_closed.store(true, Ordering::Release);
+      listeners.waiting.clear();
+      listeners.cancelled.clear();
     });
-    Ok(SynchronizedConnection { requests_tx })
+    Ok(SynchronizedConnection {
+      requests_tx,
+      listeners,
+      closed,
+    })
   }
 
-  async fn get_connection(&self) -> Result<&SynchronizedConnection, io::Error> {
-    self
-      .connection
-      .get_or_try_init(Self::create_connection)
-      .await
+  /// Returns the current connection, reconnecting if there isn't one or the
+  /// last one was closed
+  async fn get_connection(&self) -> Result<Arc<SynchronizedConnection>, RequestError> {
+    let mut connection = self.connection.lock().await;
+    match &*connection {
+      Some(connection) if !connection.is_closed() => Ok(connection.clone()),
+      _ => {
+        let new_connection = Self::create_connection(&self.address, self.log_level)
+          .await
+          .map_err(|err| match &self.address {
+            BackendAddress::Unix(path) if err.kind() == io::ErrorKind::NotFound => {
+              RequestError::BackendUnavailable(path.clone())
+            }
+            _ => err.into(),
+          })?;
+        let new_connection = Arc::new(new_connection);
+        let mut lifecycle = self.lifecycle.lock().unwrap();
+        lifecycle.current = Some((Instant::now(), new_connection.closed.clone()));
+        lifecycle.connections += 1;
+        drop(lifecycle);
+        *connection = Some(new_connection.clone());
+        Ok(new_connection)
+      }
+    }
+  }
+
+  /// Connects to the backend and checks that it answers a
+  /// [`RequestBody::Ping`], instead of waiting for the first request to do
+  /// it. Call it during a loading screen so the first real request doesn't
+  /// pay for connecting, and so a missing socket is reported early.
+  ///
+  /// Does nothing if the client is already connected.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::default();
+  /// if let Err(err) = futures_lite::future::block_on(async_compat::Compat::new(client.connect())) {
+  ///   println!("{}", err.user_message());
+  /// }
+  /// ```
+  pub async fn connect(&self) -> Result<(), RequestError> {
+    if matches!(&*self.connection.lock().await, Some(connection) if !connection.is_closed()) {
+      return Ok(());
+    }
+    self.ping().await.map(|_| ())
   }
 
   /// Sends a request to the backend and returns the corresponding response.
   /// If the response is [`ResponseBody::Err`],
   /// a [`RequestError::ResponseError`] is returned instead with the error
   /// message.
+  ///
+  /// # Example
+  /// Requests share one connection, which can be used for as long as the
+  /// backend keeps it open:
+  /// ```no_run
+  /// use devcaders::devcade_onboard_types::RequestBody;
+  /// use devcaders::{BackendClient, ResponseBodyExt};
+  ///
+  /// let client = BackendClient::default();
+  /// let user = futures_lite::future::block_on(async_compat::Compat::new(
+  ///   client.send(RequestBody::GetNfcUser("badge-1234".to_owned())),
+  /// ));
+  /// println!("{:?}", user.and_then(|user| user.expect_nfc_user()));
+  /// ```
   pub async fn send(&self, body: RequestBody) -> Result<ResponseBody, RequestError> {
+    self.send_observed(body, None).await
+  }
+
+  /// Sends `body`, and tells the [completion hook](BackendClient::on_complete)
+  /// how it went. With a `timeout` of `(limit, waited)`, gives up after
+  /// `limit` with a [`RequestError::Timeout`] that reports `waited`, without
+  /// sending anything if `limit` is zero
+  async fn send_observed(
+    &self,
+    body: RequestBody,
+    timeout: Option<(Duration, Duration)>,
+  ) -> Result<ResponseBody, RequestError> {
+    let started = Instant::now();
+    let timed_out = |waited| RequestError::Timeout {
+      waited,
+      request: Box::new(body.clone()),
+    };
+    let result = match timeout {
+      None => self.send_once(body.clone()).await,
+      Some((limit, waited)) if limit.is_zero() => Err(timed_out(waited)),
+      Some((limit, waited)) => tokio::time::timeout(limit, self.send_once(body.clone()))
+        .await
+        .unwrap_or_else(|_| Err(timed_out(waited))),
+    };
+    if let Some(on_complete) = &self.on_complete {
+      on_complete(&body, result.as_ref(), started.elapsed());
+    }
+    result
+  }
+
+  async fn send_once(&self, body: RequestBody) -> Result<ResponseBody, RequestError> {
+    let _pending = PendingGuard::new(&self.pending);
+    if let Some(rate_limiter) = &self.rate_limiter {
+      rate_limiter.acquire().await?;
+    }
     let connection = self.get_connection().await?;
     let (tx, rx) = oneshot::channel();
+    let mut response = ResponseReceiver {
+      rx,
+      listeners: connection.listeners.clone(),
+    };
     connection
       .requests_tx
       .send((body, tx))
       .await
       .map_err(|_| RequestError::ChannelClosed)?;
-    match rx.await.map_err(|_| RequestError::ChannelClosed) {
+    match (&mut response.rx)
+      .await
+      .map_err(|_| RequestError::ChannelClosed)
+    {
       Ok(Ok(response)) => Ok(response),
       Ok(Err(err)) | Err(err) => Err(err),
     }
   }
+
+  /// Like [`BackendClient::send_timeout`], with the time left before
+  /// `deadline` as the timeout. Fails with [`RequestError::Timeout`] without
+  /// sending anything if the deadline has already passed
+  pub async fn send_before(
+    &self,
+    body: RequestBody,
+    deadline: &Deadline,
+  ) -> Result<ResponseBody, RequestError> {
+    self
+      .send_observed(body, Some((deadline.remaining(), deadline.budget)))
+      .await
+  }
+
+  /// Like [`BackendClient::send`], but gives up with
+  /// [`RequestError::Timeout`] if the backend hasn't answered within
+  /// `timeout`. The error carries the request and how long was waited, so
+  /// it can be reported on screen.
+  pub async fn send_timeout(
+    &self,
+    body: RequestBody,
+    timeout: Duration,
+  ) -> Result<ResponseBody, RequestError> {
+    self.send_observed(body, Some((timeout, timeout))).await
+  }
+
+  /// Returns how many requests have been sent and are still waiting for a
+  /// response
+  pub fn pending_requests(&self) -> usize {
+    self.pending.load(Ordering::Relaxed)
+  }
+
+  /// Returns how the client and its connection are doing, for keeping an
+  /// eye on deployed cabinets
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::default();
+  /// let stats = client.stats();
+  /// // Nothing has been sent yet
+  /// assert_eq!(stats.connected_since, None);
+  /// assert_eq!(stats.reconnect_count, 0);
+  /// assert_eq!(stats.pending_requests, 0);
+  /// ```
+  pub fn stats(&self) -> ClientStats {
+    let lifecycle = self.lifecycle.lock().unwrap();
+    let connected_since = lifecycle
+      .current
+      .as_ref()
+      .filter(|(_, closed)| !closed.load(Ordering::Acquire))
+      .map(|&(since, _)| since);
+    ClientStats {
+      pending_requests: self.pending_requests(),
+      connected_since,
+      reconnect_count: lifecycle.connections.saturating_sub(1),
+    }
+  }
+
+  /// Checks that the backend is up and answering requests
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::default();
+  /// match futures_lite::future::block_on(async_compat::Compat::new(client.ping())) {
+  ///   Ok(_) => println!("Backend is up"),
+  ///   Err(err) => println!("{}", err.user_message()),
+  /// }
+  /// ```
+  pub async fn ping(&self) -> Result<Pong, RequestError> {
+    self.send(RequestBody::Ping).await?.expect_pong()
+  }
+
+  /// Lists the games installed on the cabinet, e.g. to show a game's own
+  /// metadata or suggest related titles
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::default();
+  /// let games = futures_lite::future::block_on(async_compat::Compat::new(client.list_games()));
+  /// for game in games.unwrap() {
+  ///   println!("{}", game.name);
+  /// }
+  /// ```
+  pub async fn list_games(&self) -> Result<Vec<DevcadeGame>, RequestError> {
+    self
+      .send(RequestBody::GetGameList)
+      .await?
+      .expect_game_list()
+  }
+
+  /// Sends `request` and pulls a `Resp` out of the answer
+  ///
+  /// New kinds of requests can be added by implementing [`IntoRequestBody`]
+  /// and [`FromResponseBody`] instead of writing a method for each.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::devcade_onboard_types::{RequestBody, ResponseBody};
+  /// use devcaders::{BackendClient, FromResponseBody, IntoRequestBody, RequestError};
+  ///
+  /// struct GetGame(&'static str);
+  ///
+  /// impl IntoRequestBody for GetGame {
+  ///   fn into_request_body(self) -> RequestBody {
+  ///     RequestBody::GetGame(self.0.to_owned())
+  ///   }
+  /// }
+  ///
+  /// struct GameName(String);
+  ///
+  /// impl FromResponseBody for GameName {
+  ///   fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+  ///     match body {
+  ///       ResponseBody::Game(game) => Ok(GameName(game.name)),
+  ///       body => Err(RequestError::UnexpectedResponse {
+  ///         expected: "Game",
+  ///         actual: Box::new(body),
+  ///       }),
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// let client = BackendClient::default();
+  /// let name = futures_lite::future::block_on(async_compat::Compat::new(
+  ///   client.request_typed::<_, GameName>(GetGame("tetris")),
+  /// ));
+  /// ```
+  pub async fn request_typed<Req: IntoRequestBody, Resp: FromResponseBody>(
+    &self,
+    request: Req,
+  ) -> Result<Resp, RequestError> {
+    Resp::from_response_body(self.send(request.into_request_body()).await?)
+  }
+
+  /// Starts building a request to send with this client
+  ///
+  /// # Example
+  /// ```
+  /// use devcaders::devcade_onboard_types::{Player, RequestBody};
+  /// use devcaders::BackendClient;
+  ///
+  /// let client = BackendClient::with_path("/tmp/devcade/game.sock");
+  /// let request = client.request().nfc_tag(Player::P2);
+  /// assert!(matches!(request.body(), RequestBody::GetNfcTag(Player::P2)));
+  /// let request = client.request().nfc_user("abc");
+  /// assert!(matches!(request.body(), RequestBody::GetNfcUser(tag) if tag == "abc"));
+  /// let request = client.request().ping();
+  /// assert!(matches!(request.body(), RequestBody::Ping));
+  /// ```
+  pub fn request(&self) -> RequestBuilder<'_> {
+    RequestBuilder { client: self }
+  }
+
+  /// Like [`BackendClient::send`], but resends the request according to
+  /// `policy` if it fails because of a connection problem, reconnecting to
+  /// the backend if needed.
+  ///
+  /// Only requests that are safe to repeat, like [`RequestBody::Ping`] and
+  /// [`RequestBody::GetNfcTag`], are retried. Others are sent once. Each
+  /// retry is logged at the client's [level](BackendClient::log_level), and
+  /// repeats are throttled the same way as its other messages.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use devcaders::devcade_onboard_types::RequestBody;
+  /// use devcaders::{BackendClient, RetryPolicy};
+  ///
+  /// let client = BackendClient::default();
+  /// let pong = futures_lite::future::block_on(async_compat::Compat::new(
+  ///   client.send_with_retry(RequestBody::Ping, RetryPolicy::default()),
+  /// ));
+  /// ```
+  pub async fn send_with_retry(
+    &self,
+    body: RequestBody,
+    policy: RetryPolicy,
+  ) -> Result<ResponseBody, RequestError> {
+    let max_attempts = if is_idempotent(&body) {
+      policy.max_attempts.max(1)
+    } else {
+      1
+    };
+    let mut backoff = policy.backoff;
+    let mut attempt = 1;
+    loop {
+      match self.send(body.clone()).await {
+        Err(err) if err.is_transient() && attempt < max_attempts => {
+          self.retry_log.lock().unwrap().log(format_args!(
+            "Request {body} failed, retrying in {backoff:?}. {err}"
+          ));
+          tokio::time::sleep(backoff).await;
+          backoff *= 2;
+          attempt += 1;
+        }
+        result => return result,
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_backend::{block_on, TestBackend};
+
+  #[test]
+  fn deadline_is_shared_between_requests() {
+    // A backend that takes 150ms to answer
+    let backend = TestBackend::serve(|request, connection| {
+      std::thread::sleep(Duration::from_millis(150));
+      connection.reply(&request, ResponseBody::Pong);
+    });
+    let timeouts = Arc::new(AtomicUsize::new(0));
+    let client = backend.client().on_complete({
+      let timeouts = timeouts.clone();
+      move |_, result, _| {
+        if matches!(result, Err(RequestError::Timeout { .. })) {
+          timeouts.fetch_add(1, Ordering::SeqCst);
+        }
+      }
+    });
+    block_on(async {
+      let budget = Duration::from_millis(250);
+      // On their own, each fits in the budget
+      for _ in 0..2 {
+        assert!(client.send_timeout(RequestBody::Ping, budget).await.is_ok());
+      }
+
+      let started = Instant::now();
+      let deadline = Deadline::after(budget);
+      assert!(client
+        .send_before(RequestBody::Ping, &deadline)
+        .await
+        .is_ok());
+      let second = client.send_before(RequestBody::Ping, &deadline).await;
+      let Err(RequestError::Timeout { waited, .. }) = second else {
+        panic!("Expected a timeout, got {second:?}");
+      };
+      assert_eq!(waited, budget);
+      assert!(started.elapsed() < Duration::from_millis(300));
+      assert!(deadline.expired());
+
+      // Nothing's sent once it's expired, but it still counts as a timeout
+      let third = client.send_before(RequestBody::Ping, &deadline).await;
+      assert!(matches!(third, Err(RequestError::Timeout { .. })));
+      assert_eq!(timeouts.load(Ordering::SeqCst), 2);
+    });
+  }
+
+  #[test]
+  fn cancelled_requests_leave_no_listener_behind() {
+    // Answers arrive well after the client has given up
+    let backend = TestBackend::serve(|request, connection| {
+      std::thread::sleep(Duration::from_millis(100));
+      connection.reply(&request, ResponseBody::Pong);
+    });
+    let client = backend.client();
+    block_on(async {
+      let timeout = Duration::from_millis(20);
+      assert!(client
+        .send_timeout(RequestBody::Ping, timeout)
+        .await
+        .is_err());
+      let connection = client.connection.lock().await.clone().unwrap();
+      assert!(connection.listeners.lock().await.waiting.is_empty());
+
+      // The late answer is dropped quietly, and the connection keeps working
+      client.ping().await.unwrap();
+      let listeners = connection.listeners.lock().await;
+      assert!(listeners.waiting.is_empty());
+      assert!(listeners.cancelled.is_empty());
+    });
+  }
+
+  #[test]
+  fn tcp_client_sends_requests() {
+    let backend = TestBackend::serve_tcp(|request, connection| {
+      let body = match request.body {
+        RequestBody::GetNfcTag(_) => ResponseBody::NfcTag(Some("badge".to_owned())),
+        _ => ResponseBody::Pong,
+      };
+      connection.reply(&request, body);
+    });
+    let client = backend.client();
+    block_on(async {
+      assert_eq!(client.ping().await.unwrap(), Pong);
+      let tag = client.request().nfc_tag(Player::P1).send().await;
+      assert_eq!(
+        tag.unwrap().expect_nfc_tag().unwrap().as_deref(),
+        Some("badge")
+      );
+    });
+  }
+
+  #[test]
+  fn completion_hook_sees_answers_and_timeouts() {
+    // Only the first request is answered
+    let answered = AtomicBool::new(false);
+    let backend = TestBackend::serve(move |request, connection| {
+      if !answered.swap(true, Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(20));
+        connection.reply(&request, ResponseBody::Pong);
+      }
+    });
+    let completed = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let client = backend.client().on_complete({
+      let completed = completed.clone();
+      move |request, result, duration| {
+        let ok = result.is_ok();
+        completed
+          .lock()
+          .unwrap()
+          .push((request.clone(), ok, duration));
+      }
+    });
+    block_on(async {
+      client.send(RequestBody::Ping).await.unwrap();
+      let timeout = Duration::from_millis(50);
+      assert!(client
+        .send_timeout(RequestBody::GetGameList, timeout)
+        .await
+        .is_err());
+    });
+
+    let completed = completed.lock().unwrap();
+    assert_eq!(completed.len(), 2);
+    let (request, ok, duration) = &completed[0];
+    assert!(matches!(request, RequestBody::Ping));
+    assert!(ok);
+    assert!(*duration >= Duration::from_millis(20));
+    let (request, ok, duration) = &completed[1];
+    assert!(matches!(request, RequestBody::GetGameList));
+    assert!(!ok);
+    assert!(*duration >= Duration::from_millis(50));
+  }
+
+  #[test]
+  fn log_level_is_used_and_repeats_are_throttled() {
+    static LOGGED: std::sync::Mutex<Vec<(log::Level, String)>> = std::sync::Mutex::new(Vec::new());
+    struct Recorder;
+    impl log::Log for Recorder {
+      fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+      }
+      fn log(&self, record: &log::Record) {
+        if record.target().starts_with("devcaders") {
+          let message = record.args().to_string();
+          LOGGED.lock().unwrap().push((record.level(), message));
+        }
+      }
+      fn flush(&self) {}
+    }
+    log::set_logger(&Recorder).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let backend = TestBackend::serve(|request, connection| {
+      for _ in 0..50 {
+        connection.send_line("not json");
+      }
+      connection.reply(&request, ResponseBody::Pong);
+    });
+    let client = backend.client().log_level(log::Level::Warn);
+    block_on(client.ping()).unwrap();
+
+    let logged = LOGGED.lock().unwrap();
+    let decode_errors: Vec<_> = logged
+      .iter()
+      .filter(|(_, message)| message.starts_with("Couldn't decode response"))
+      .collect();
+    assert_eq!(decode_errors.len(), 1);
+    assert_eq!(decode_errors[0].0, log::Level::Warn);
+  }
+
+  #[test]
+  fn rate_limit_fails_or_waits() {
+    let backend = TestBackend::respond(|_| ResponseBody::Pong);
+    block_on(async {
+      // Failing fast, a burst is cut off at the limit
+      let client = backend.client().rate_limit(10, RateLimitMode::Fail);
+      let mut results = Vec::new();
+      for _ in 0..15 {
+        results.push(client.ping().await);
+      }
+      assert!(results[..10].iter().all(Result::is_ok));
+      assert!(results[10..]
+        .iter()
+        .all(|result| matches!(result, Err(RequestError::RateLimited))));
+
+      // Waiting, the burst is spread out instead. The last 5 requests wait
+      // a tenth of a second each
+      let client = backend.client().rate_limit(10, RateLimitMode::Wait);
+      let started = Instant::now();
+      for _ in 0..15 {
+        client.ping().await.unwrap();
+      }
+      assert!(started.elapsed() >= Duration::from_millis(450));
+    });
+  }
+
+  #[test]
+  fn connect_pings_once() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let backend = TestBackend::serve({
+      let requests = requests.clone();
+      move |request, connection| {
+        requests.fetch_add(1, Ordering::SeqCst);
+        connection.reply(&request, ResponseBody::Pong);
+      }
+    });
+    let client = backend.client();
+    block_on(async {
+      client.connect().await.unwrap();
+      assert_eq!(requests.load(Ordering::SeqCst), 1);
+      client.connect().await.unwrap();
+      assert_eq!(requests.load(Ordering::SeqCst), 1);
+      client.send(RequestBody::Ping).await.unwrap();
+    });
+    assert_eq!(backend.connections(), 1);
+
+    let missing = BackendClient::with_path(std::env::temp_dir().join("devcaders-missing.sock"));
+    assert!(block_on(missing.connect()).is_err());
+  }
+
+  #[test]
+  fn requests_share_one_connection() {
+    let backend = TestBackend::respond(|body| match body {
+      RequestBody::GetNfcUser(uid) => {
+        let mut user = Map::new();
+        user.insert("uid".to_owned(), uid.clone().into());
+        user.insert("photo".to_owned(), "x".repeat(1 << 20).into());
+        ResponseBody::NfcUser(user)
+      }
+      _ => ResponseBody::Pong,
+    });
+    let client = backend.client();
+    block_on(async {
+      let user = client
+        .send(RequestBody::GetNfcUser("mstrodl".to_owned()))
+        .await;
+      assert_eq!(user.unwrap().expect_nfc_user().unwrap()["uid"], "mstrodl");
+      for _ in 0..1000 {
+        let pong = client.send(RequestBody::Ping).await;
+        assert!(matches!(pong, Ok(ResponseBody::Pong)));
+      }
+    });
+    assert_eq!(backend.connections(), 1);
+  }
+
+  #[test]
+  fn reset_buffer_gives_back_big_frames() {
+    let mut buffer = vec![0; RETAINED_BUFFER_CAPACITY * 4];
+    reset_buffer(&mut buffer);
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() <= RETAINED_BUFFER_CAPACITY);
+
+    // Small frames keep their memory for the next one
+    let mut buffer = Vec::with_capacity(1024);
+    buffer.extend_from_slice(b"{}");
+    reset_buffer(&mut buffer);
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() >= 1024);
+  }
+
+  #[test]
+  fn stats_track_reconnects() {
+    let backend = TestBackend::respond(|_| ResponseBody::Pong);
+    let client = backend.client();
+    let stats = client.stats();
+    assert_eq!(stats.connected_since, None);
+    assert_eq!(stats.reconnect_count, 0);
+
+    block_on(async {
+      client.send(RequestBody::Ping).await.unwrap();
+      let first = client.stats().connected_since.unwrap();
+      assert_eq!(client.stats().reconnect_count, 0);
+
+      // The backend drops the connection, and the client notices
+      backend.disconnect();
+      while client.stats().connected_since.is_some() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+      }
+      assert_eq!(client.stats().reconnect_count, 0);
+
+      client.send(RequestBody::Ping).await.unwrap();
+      let stats = client.stats();
+      assert!(stats.connected_since.unwrap() > first);
+      assert_eq!(stats.reconnect_count, 1);
+      assert_eq!(stats.pending_requests, 0);
+    });
+  }
+
+  #[test]
+  fn ping_expects_pong() {
+    // A working backend, then a confused one
+    let pinged = AtomicBool::new(false);
+    let backend = TestBackend::respond(move |_| {
+      if pinged.swap(true, Ordering::SeqCst) {
+        ResponseBody::NfcTag(None)
+      } else {
+        ResponseBody::Pong
+      }
+    });
+    let client = backend.client();
+    block_on(async {
+      assert_eq!(client.ping().await.unwrap(), Pong);
+      assert!(matches!(
+        client.ping().await,
+        Err(RequestError::UnexpectedResponse { .. })
+      ));
+    });
+  }
+
+  #[test]
+  fn list_games_returns_games() {
+    let backend = TestBackend::respond(|body| match body {
+      RequestBody::GetGameList => ResponseBody::GameList(
+        ["tetris", "bankshot"]
+          .map(|id| DevcadeGame {
+            id: id.to_owned(),
+            name: id.to_uppercase(),
+            ..Default::default()
+          })
+          .into(),
+      ),
+      _ => ResponseBody::Pong,
+    });
+    let games = block_on(backend.client().list_games());
+    let names: Vec<_> = games.unwrap().into_iter().map(|game| game.name).collect();
+    assert_eq!(names, ["TETRIS", "BANKSHOT"]);
+  }
+
+  #[test]
+  fn request_typed_converts_both_ways() {
+    struct GetGame(&'static str);
+
+    impl IntoRequestBody for GetGame {
+      fn into_request_body(self) -> RequestBody {
+        RequestBody::GetGame(self.0.to_owned())
+      }
+    }
+
+    struct GameName(String);
+
+    impl FromResponseBody for GameName {
+      fn from_response_body(body: ResponseBody) -> Result<Self, RequestError> {
+        match body {
+          ResponseBody::Game(game) => Ok(GameName(game.name)),
+          body => Err(RequestError::UnexpectedResponse {
+            expected: "Game",
+            actual: Box::new(body),
+          }),
+        }
+      }
+    }
+
+    let backend = TestBackend::respond(|body| match body {
+      RequestBody::GetGame(id) => ResponseBody::Game(DevcadeGame {
+        name: id.to_uppercase(),
+        ..Default::default()
+      }),
+      _ => ResponseBody::Pong,
+    });
+    let client = backend.client();
+    block_on(async {
+      let GameName(name) = client.request_typed(GetGame("tetris")).await.unwrap();
+      assert_eq!(name, "TETRIS");
+
+      let pong: Pong = client.request_typed(RequestBody::Ping).await.unwrap();
+      assert_eq!(pong, Pong);
+
+      let wrong: Result<GameName, _> = client.request_typed(RequestBody::Ping).await;
+      assert!(matches!(
+        wrong,
+        Err(RequestError::UnexpectedResponse { .. })
+      ));
+    });
+  }
+
+  #[test]
+  fn send_with_retry_reconnects() {
+    // Hang up on the first request, answer the retry
+    let hung_up = AtomicBool::new(false);
+    let backend = TestBackend::serve(move |request, connection| {
+      if hung_up.swap(true, Ordering::SeqCst) {
+        connection.reply(&request, ResponseBody::Pong);
+      } else {
+        connection.hang_up();
+      }
+    });
+    let response = block_on(
+      backend
+        .client()
+        .send_with_retry(RequestBody::Ping, RetryPolicy::default()),
+    );
+    assert!(matches!(response, Ok(ResponseBody::Pong)));
+    assert_eq!(backend.connections(), 2);
+  }
 }
@@ -0,0 +1,759 @@
+use crate::Player;
+use bevy::prelude::*;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Settings for how [`DevcadeControls`](crate::DevcadeControls) reads input
+///
+/// Insert your own copy of this resource to change them.
+///
+/// # Examples
+/// Ignore buttons that chatter for less than 50ms:
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::DevcadeInputConfig;
+/// use std::time::Duration;
+///
+/// let mut app = App::new();
+/// app.insert_resource(DevcadeInputConfig {
+///   debounce: Duration::from_millis(50),
+///   ..default()
+/// });
+/// ```
+///
+/// Let someone play on the keyboard while a controller is plugged in:
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::DevcadeInputConfig;
+///
+/// let mut app = App::new();
+/// app.insert_resource(DevcadeInputConfig {
+///   merge_keyboard_and_gamepad: true,
+///   ..default()
+/// });
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct DevcadeInputConfig {
+  /// How long a button has to stay pressed or released before the change is
+  /// reported. Useful for buttons that chatter. Defaults to zero, which
+  /// reports every change immediately.
+  pub debounce: Duration,
+  /// Read the keyboard as well as a player's controller instead of only
+  /// falling back to it when the controller is missing. Defaults to false.
+  pub merge_keyboard_and_gamepad: bool,
+  /// Read the keyboard for players without a controller. Defaults to true.
+  /// Turn it off on cabinets where the keyboard isn't reachable, so
+  /// [`DevcadeNoInputAvailable`](crate::DevcadeNoInputAvailable) is sent
+  /// when no controllers are connected.
+  pub keyboard_fallback: bool,
+  /// Whether the controller's stick can press two directions at once
+  pub stick_mode: StickMode,
+  /// What happens when opposite stick directions are held on the keyboard
+  pub socd: SocdMode,
+  /// How long a stick direction has to be held before
+  /// [`DevcadeControls::stick_nav_just_pressed`](crate::DevcadeControls::stick_nav_just_pressed)
+  /// starts repeating. Defaults to 400ms.
+  pub stick_nav_delay: Duration,
+  /// How often
+  /// [`DevcadeControls::stick_nav_just_pressed`](crate::DevcadeControls::stick_nav_just_pressed)
+  /// repeats once it's started. Defaults to 100ms, and must be more than
+  /// zero.
+  pub stick_nav_interval: Duration,
+  /// Flip the stick's horizontal axis, for cabinets where pushing the stick
+  /// right reads as left. Defaults to false.
+  pub invert_x: bool,
+  /// Flip the stick's vertical axis, for cabinets where pushing the stick up
+  /// reads as down. Defaults to false.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeInputConfig;
+  ///
+  /// // A cabinet whose stick is wired upside down
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   invert_y: true,
+  ///   ..default()
+  /// });
+  /// ```
+  pub invert_y: bool,
+  /// How long the set of connected controllers has to stay the same before
+  /// players are reassigned to them. Keeps a controller that briefly drops
+  /// out from shuffling everyone's controls. Defaults to zero, which
+  /// reassigns immediately.
+  ///
+  /// Players are assigned controllers in order of their ids, so
+  /// [`Player::P1`](crate::Player::P1) gets the one with the lowest id.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeInputConfig;
+  /// use std::time::Duration;
+  ///
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   gamepad_settle: Duration::from_millis(500),
+  ///   ..default()
+  /// });
+  /// ```
+  pub gamepad_settle: Duration,
+  /// How long a player's input is held as it was after one of their
+  /// controllers disconnects, waiting for it to come back. Defaults to 1
+  /// second. Zero turns this off.
+  ///
+  /// A loose cable shouldn't look like the player letting go of everything
+  /// and grabbing it again. While the controller's gone, the player's buttons
+  /// and stick stay where they were, and when it comes back to the same
+  /// player, the first frame of input from it is taken without any
+  /// [`just_pressed`](crate::DevcadeControls::just_pressed) or
+  /// [`just_released`](crate::DevcadeControls::just_released). If it isn't
+  /// back in time, input picks up from whatever the player has now, releases
+  /// and all.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeInputConfig;
+  /// use std::time::Duration;
+  ///
+  /// // Cabinets with flaky USB hubs
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   reconnect_grace: Duration::from_secs(3),
+  ///   ..default()
+  /// });
+  /// ```
+  pub reconnect_grace: Duration,
+  /// How many controllers each player gets, for cabinets that wire a
+  /// player's stick and buttons up as separate devices. Defaults to 1.
+  ///
+  /// Controllers are handed out in order of their ids, so with 2,
+  /// [`Player::P1`](crate::Player::P1) gets the two lowest. A player's
+  /// controllers are read as one: a button is pressed if it's pressed on any
+  /// of them, and each stick axis is read from the first controller where
+  /// it's pushed past the [`deadzone`](DevcadeInputConfig::deadzone). Values
+  /// below 1 are treated as 1.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeInputConfig;
+  ///
+  /// // Each cabinet side has a stick pad and a button pad
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   gamepads_per_player: 2,
+  ///   ..default()
+  /// });
+  /// ```
+  pub gamepads_per_player: usize,
+  /// How far the controller's stick has to be pushed along an axis, from 0
+  /// to 1, before it counts. Applies to both
+  /// [`DevcadeControls::stick`](crate::DevcadeControls::stick) and the stick
+  /// buttons, so they always agree. Defaults to zero, where any movement
+  /// counts.
+  pub deadzone: f32,
+  /// Reshapes how far the stick reads as pushed, for
+  /// [`DevcadeControls::stick`](crate::DevcadeControls::stick). Defaults to
+  /// [`ResponseCurve::Linear`].
+  ///
+  /// The curve is applied to how far the stick is pushed, after the deadzone,
+  /// so the direction never changes. Since the stick buttons only care about
+  /// direction, they aren't affected. See
+  /// [`apply_stick_response`](DevcadeInputConfig::apply_stick_response).
+  pub stick_curve: ResponseCurve,
+  /// Multiplies the result of [`stick_curve`](DevcadeInputConfig::stick_curve).
+  /// The result is capped at being pushed all the way. Defaults to 1.
+  pub stick_sensitivity: f32,
+  /// Players whose controls are mirrored left to right, for players who'd
+  /// rather have the stick on the right. See [`Button::mirrored`](crate::Button::mirrored)
+  /// for exactly what's swapped. Defaults to nobody.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::{DevcadeInputConfig, Player};
+  ///
+  /// // P1 plays left-handed
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   mirror_layout: [Player::P1].into(),
+  ///   ..default()
+  /// });
+  /// ```
+  pub mirror_layout: HashSet<Player>,
+  /// How far a trigger-backed button ([`Button::A3`](crate::Button::A3),
+  /// [`Button::A4`](crate::Button::A4), [`Button::B3`](crate::Button::B3) and
+  /// [`Button::B4`](crate::Button::B4)) has to be pulled, from 0 to 1, to
+  /// count as pressed. Defaults to `None`, which leaves it up to bevy's
+  /// [`GamepadSettings`](bevy::input::gamepad::GamepadSettings).
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeInputConfig;
+  ///
+  /// // Count a light pull of the triggers as a press
+  /// let mut app = App::new();
+  /// app.insert_resource(DevcadeInputConfig {
+  ///   trigger_threshold: Some(0.3),
+  ///   ..default()
+  /// });
+  /// ```
+  pub trigger_threshold: Option<f32>,
+  /// How many frames of input
+  /// [`DevcadeControls::input_history`](crate::DevcadeControls::input_history)
+  /// keeps. Defaults to 120.
+  #[cfg(feature = "debug-input-history")]
+  pub input_history_len: usize,
+}
+
+impl Default for DevcadeInputConfig {
+  fn default() -> Self {
+    Self {
+      debounce: Duration::ZERO,
+      merge_keyboard_and_gamepad: false,
+      keyboard_fallback: true,
+      stick_mode: StickMode::default(),
+      socd: SocdMode::default(),
+      stick_nav_delay: Duration::from_millis(400),
+      stick_nav_interval: Duration::from_millis(100),
+      invert_x: false,
+      invert_y: false,
+      gamepad_settle: Duration::ZERO,
+      reconnect_grace: Duration::from_secs(1),
+      gamepads_per_player: 1,
+      deadzone: 0.0,
+      stick_curve: ResponseCurve::default(),
+      stick_sensitivity: 1.0,
+      mirror_layout: HashSet::new(),
+      trigger_threshold: None,
+      #[cfg(feature = "debug-input-history")]
+      input_history_len: 120,
+    }
+  }
+}
+
+impl DevcadeInputConfig {
+  /// Corrects settings that don't make sense, logging a warning for each,
+  /// and returns true if any needed correcting
+  ///
+  /// [`deadzone`](DevcadeInputConfig::deadzone) and
+  /// [`trigger_threshold`](DevcadeInputConfig::trigger_threshold) are clamped
+  /// between 0 and 1,
+  /// [`gamepads_per_player`](DevcadeInputConfig::gamepads_per_player) is
+  /// raised to 1, and a zero
+  /// [`stick_nav_interval`](DevcadeInputConfig::stick_nav_interval) or a NaN
+  /// or negative [`stick_sensitivity`](DevcadeInputConfig::stick_sensitivity)
+  /// goes back to its default. A [`ResponseCurve::Exponent`] that isn't
+  /// positive becomes [`ResponseCurve::Linear`]. [`DevcadePlugin`](crate::DevcadePlugin) calls
+  /// this whenever the resource changes.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::{input::InputPlugin, prelude::*};
+  /// use devcaders::{DevcadeInputConfig, DevcadePlugin, ResponseCurve};
+  /// use std::time::Duration;
+  ///
+  /// let mut config = DevcadeInputConfig::default();
+  /// assert!(!config.validate());
+  ///
+  /// let mut app = App::new();
+  /// app
+  ///   .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+  ///   .insert_resource(DevcadeInputConfig {
+  ///     deadzone: -0.5,
+  ///     trigger_threshold: Some(1.5),
+  ///     gamepads_per_player: 0,
+  ///     stick_nav_interval: Duration::ZERO,
+  ///     stick_sensitivity: -2.0,
+  ///     stick_curve: ResponseCurve::Exponent(0.0),
+  ///     ..default()
+  ///   });
+  /// app.update();
+  ///
+  /// let config = app.world.resource::<DevcadeInputConfig>();
+  /// assert_eq!(config.deadzone, 0.0);
+  /// assert_eq!(config.trigger_threshold, Some(1.0));
+  /// assert_eq!(config.gamepads_per_player, 1);
+  /// assert_eq!(config.stick_nav_interval, Duration::from_millis(100));
+  /// assert_eq!(config.stick_sensitivity, 1.0);
+  /// assert_eq!(config.stick_curve, ResponseCurve::Linear);
+  ///
+  /// // Changing it later is caught too
+  /// let mut config = app.world.resource_mut::<DevcadeInputConfig>();
+  /// config.deadzone = f32::NAN;
+  /// config.stick_sensitivity = f32::NAN;
+  /// config.stick_curve = ResponseCurve::Exponent(f32::NAN);
+  /// app.update();
+  /// let config = app.world.resource::<DevcadeInputConfig>();
+  /// assert_eq!(config.deadzone, 0.0);
+  /// assert_eq!(config.stick_sensitivity, 1.0);
+  /// assert_eq!(config.stick_curve, ResponseCurve::Linear);
+  ///
+  /// for exponent in [-1.0, f32::INFINITY] {
+  ///   let mut config = DevcadeInputConfig {
+  ///     stick_curve: ResponseCurve::Exponent(exponent),
+  ///     ..default()
+  ///   };
+  ///   assert!(config.validate());
+  ///   assert_eq!(config.stick_curve, ResponseCurve::Linear);
+  /// }
+  /// ```
+  pub fn validate(&mut self) -> bool {
+    let mut corrected = false;
+    let deadzone = unit_interval(self.deadzone);
+    if deadzone != self.deadzone {
+      log::warn!(
+        "Input deadzone {} is out of range, using {deadzone}",
+        self.deadzone
+      );
+      self.deadzone = deadzone;
+      corrected = true;
+    }
+    if let Some(threshold) = self.trigger_threshold {
+      let clamped = unit_interval(threshold);
+      if clamped != threshold {
+        log::warn!("Trigger threshold {threshold} is out of range, using {clamped}");
+        self.trigger_threshold = Some(clamped);
+        corrected = true;
+      }
+    }
+    if self.gamepads_per_player == 0 {
+      log::warn!("Players need at least one gamepad each, using 1");
+      self.gamepads_per_player = 1;
+      corrected = true;
+    }
+    let defaults = Self::default();
+    if self.stick_nav_interval.is_zero() {
+      log::warn!(
+        "Stick nav interval can't be zero, using {:?}",
+        defaults.stick_nav_interval
+      );
+      self.stick_nav_interval = defaults.stick_nav_interval;
+      corrected = true;
+    }
+    if self.stick_sensitivity.is_nan() || self.stick_sensitivity < 0.0 {
+      log::warn!(
+        "Stick sensitivity {} is out of range, using {}",
+        self.stick_sensitivity,
+        defaults.stick_sensitivity
+      );
+      self.stick_sensitivity = defaults.stick_sensitivity;
+      corrected = true;
+    }
+    if let ResponseCurve::Exponent(exponent) = self.stick_curve {
+      if !exponent.is_finite() || exponent <= 0.0 {
+        log::warn!("Stick curve exponent {exponent} is out of range, using a linear curve");
+        self.stick_curve = ResponseCurve::Linear;
+        corrected = true;
+      }
+    }
+    corrected
+  }
+
+  /// Applies [`stick_curve`](DevcadeInputConfig::stick_curve) and
+  /// [`stick_sensitivity`](DevcadeInputConfig::stick_sensitivity) to `stick`
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::{DevcadeInputConfig, ResponseCurve};
+  ///
+  /// let config = DevcadeInputConfig {
+  ///   stick_curve: ResponseCurve::Squared,
+  ///   ..default()
+  /// };
+  /// // Small movements are softened, full movements stay full
+  /// assert_eq!(config.apply_stick_response(Vec2::new(0.5, 0.0)), Vec2::new(0.25, 0.0));
+  /// assert_eq!(config.apply_stick_response(Vec2::new(0.0, -1.0)), Vec2::new(0.0, -1.0));
+  ///
+  /// for stick_curve in [ResponseCurve::Linear, ResponseCurve::Squared, ResponseCurve::Exponent(0.5)] {
+  ///   let config = DevcadeInputConfig {
+  ///     stick_curve,
+  ///     stick_sensitivity: 1.5,
+  ///     ..default()
+  ///   };
+  ///   let mut last = 0.0;
+  ///   for i in 1..=10 {
+  ///     let stick = Vec2::new(-0.6, 0.8) * i as f32 / 10.0;
+  ///     let applied = config.apply_stick_response(stick);
+  ///     // Pushing further never reads as less pushed
+  ///     assert!(applied.length() >= last - 1e-6);
+  ///     last = applied.length();
+  ///     // Still pointing the same way
+  ///     assert!(applied.normalize().abs_diff_eq(stick.normalize(), 1e-6));
+  ///   }
+  /// }
+  /// ```
+  pub fn apply_stick_response(&self, stick: Vec2) -> Vec2 {
+    let length = stick.length().min(1.0);
+    if length == 0.0 {
+      return stick;
+    }
+    let curved = match self.stick_curve {
+      ResponseCurve::Linear => length,
+      ResponseCurve::Squared => length * length,
+      ResponseCurve::Exponent(exponent) => length.powf(exponent),
+    };
+    stick.normalize() * (curved * self.stick_sensitivity).clamp(0.0, 1.0)
+  }
+}
+
+/// Clamps `value` between 0 and 1, treating NaN as 0
+fn unit_interval(value: f32) -> f32 {
+  if value.is_nan() {
+    0.0
+  } else {
+    value.clamp(0.0, 1.0)
+  }
+}
+
+/// Validates the [`DevcadeInputConfig`] whenever it changes
+pub(crate) fn validate_input_config(mut config: ResMut<DevcadeInputConfig>) {
+  if !config.is_changed() {
+    return;
+  }
+  let mut validated = config.clone();
+  if validated.validate() {
+    *config = validated;
+  }
+}
+
+/// How the controller's stick is turned into [`Button::StickUp`](crate::Button::StickUp)
+/// and friends
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{DevcadeInputConfig, StickMode};
+///
+/// // Menus that only move one way at a time
+/// fn enter_menu(mut config: ResMut<DevcadeInputConfig>) {
+///   config.stick_mode = StickMode::FourWay;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StickMode {
+  /// Diagonals press both directions. The default
+  #[default]
+  EightWay,
+  /// Only the direction along the axis the stick is pushed furthest on is
+  /// pressed. Ties go to the horizontal direction
+  FourWay,
+}
+
+/// How opposite stick directions held at the same time are resolved, for
+/// [`DevcadeInputConfig::socd`]
+///
+/// A real stick can't point two ways at once, but the keyboard can hold both
+/// [`StickLeft`](crate::Button::StickLeft) and
+/// [`StickRight`](crate::Button::StickRight). Each axis is resolved on its
+/// own. Directions that start being held on the same frame always cancel
+/// out.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{DevcadeInputConfig, SocdMode};
+///
+/// let mut app = App::new();
+/// app.insert_resource(DevcadeInputConfig {
+///   socd: SocdMode::LastWins,
+///   ..default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SocdMode {
+  /// Opposite directions cancel out, leaving the axis centered. The default
+  #[default]
+  Neutral,
+  /// The direction pressed most recently wins
+  LastWins,
+  /// The direction that was held first wins
+  FirstWins,
+}
+
+/// Curves for [`DevcadeInputConfig::stick_curve`], mapping how far the stick is pushed from 0
+/// to 1 onto 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ResponseCurve {
+  /// Reported as pushed exactly as far as it is. The default
+  #[default]
+  Linear,
+  /// Squares the distance, for finer control near the center
+  Squared,
+  /// Raises the distance to the given power. Above 1 softens movement near
+  /// the center, below 1 boosts it
+  Exponent(f32),
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fake_gamepad::{
+    connect_gamepad, controls_app, disconnect_gamepad, keyboard_app, press_gamepad_button,
+    press_key, release_key, set_stick, update_and_read, Read,
+  };
+  use crate::Button;
+  use bevy::time::TimeUpdateStrategy;
+
+  #[test]
+  fn keyboard_is_ignored_with_a_controller_unless_merged() {
+    let (mut app, _) = controls_app(DevcadeInputConfig::default(), |input| {
+      input.pressed(Player::P1, Button::A1)
+    });
+    press_key(&mut app, KeyCode::Q);
+    assert!(!update_and_read::<bool>(&mut app));
+
+    app
+      .world
+      .resource_mut::<DevcadeInputConfig>()
+      .merge_keyboard_and_gamepad = true;
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn invert_y_flips_the_stick() {
+    let (mut app, gamepad) = controls_app(DevcadeInputConfig::default(), |input| {
+      input.pressed(Player::P1, Button::StickUp)
+    });
+    // A correctly wired stick pushed up
+    set_stick(&mut app, gamepad, 0.0, 0.8);
+    assert!(update_and_read::<bool>(&mut app));
+
+    // A stick wired upside down pushed up
+    app.world.resource_mut::<DevcadeInputConfig>().invert_y = true;
+    set_stick(&mut app, gamepad, 0.0, -0.8);
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn trigger_threshold_decides_when_triggers_press() {
+    let config = DevcadeInputConfig {
+      trigger_threshold: Some(0.3),
+      ..default()
+    };
+    let (mut app, gamepad) = controls_app(config, |input| input.pressed(Player::P1, Button::B3));
+    let trigger = GamepadButton::new(gamepad, GamepadButtonType::RightTrigger2);
+
+    // A light touch
+    app
+      .world
+      .resource_mut::<Axis<GamepadButton>>()
+      .set(trigger, 0.2);
+    assert!(!update_and_read::<bool>(&mut app));
+
+    // Pulled far enough
+    app
+      .world
+      .resource_mut::<Axis<GamepadButton>>()
+      .set(trigger, 0.4);
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn four_way_stick_presses_one_direction() {
+    let (mut app, gamepad) = controls_app(DevcadeInputConfig::default(), |input| {
+      input.pressed_buttons(Player::P1).collect::<Vec<_>>()
+    });
+    // Up and to the left, but mostly left
+    set_stick(&mut app, gamepad, -0.8, 0.5);
+    assert_eq!(
+      update_and_read::<Vec<Button>>(&mut app),
+      [Button::StickLeft, Button::StickUp]
+    );
+
+    app.world.resource_mut::<DevcadeInputConfig>().stick_mode = StickMode::FourWay;
+    assert_eq!(
+      update_and_read::<Vec<Button>>(&mut app),
+      [Button::StickLeft]
+    );
+  }
+
+  #[test]
+  fn debounce_ignores_short_flickers() {
+    let config = DevcadeInputConfig {
+      debounce: Duration::from_millis(50),
+      ..default()
+    };
+    let mut app = keyboard_app(config, |input| input.pressed(Player::P1, Button::A1));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      10,
+    )));
+
+    // A 10ms flicker is ignored
+    press_key(&mut app, KeyCode::Q);
+    app.update();
+    release_key(&mut app, KeyCode::Q);
+    assert!(!update_and_read::<bool>(&mut app));
+
+    // Holding the button for 50ms registers
+    press_key(&mut app, KeyCode::Q);
+    for _ in 0..5 {
+      assert!(!update_and_read::<bool>(&mut app));
+    }
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn mirror_layout_swaps_sides() {
+    let config = DevcadeInputConfig {
+      mirror_layout: [Player::P1].into(),
+      ..default()
+    };
+    let mut app = keyboard_app(config, |input| {
+      input.pressed_buttons(Player::P1).collect::<Vec<_>>()
+    });
+    // The physical A4 and stick left
+    press_key(&mut app, KeyCode::R);
+    press_key(&mut app, KeyCode::V);
+    assert_eq!(
+      update_and_read::<Vec<Button>>(&mut app),
+      [Button::A1, Button::StickRight]
+    );
+  }
+
+  #[test]
+  fn paired_gamepads_read_as_one_player() {
+    let config = DevcadeInputConfig {
+      gamepads_per_player: 2,
+      ..default()
+    };
+    let mut app = keyboard_app(config, |input| {
+      let p1: Vec<_> = input.pressed_buttons(Player::P1).collect();
+      let p2: Vec<_> = input.pressed_buttons(Player::P2).collect();
+      (p1, p2)
+    });
+    let pads: Vec<_> = (0..4).map(Gamepad::new).collect();
+    for &pad in &pads {
+      connect_gamepad(&mut app, pad);
+    }
+
+    // P1's stick on one pad and a button on the other
+    set_stick(&mut app, pads[0], -1.0, 0.0);
+    press_gamepad_button(&mut app, pads[1], GamepadButtonType::West);
+    // P2's button pad has its stick pushed, but the stick pad wins
+    set_stick(&mut app, pads[2], 0.0, 1.0);
+    set_stick(&mut app, pads[3], 0.0, -1.0);
+    let (p1, p2) = update_and_read::<(Vec<Button>, Vec<Button>)>(&mut app);
+    assert_eq!(p1, [Button::A1, Button::StickLeft]);
+    assert_eq!(p2, [Button::StickUp]);
+  }
+
+  #[test]
+  fn socd_modes_resolve_opposite_directions() {
+    for (socd, both_held) in [
+      (SocdMode::Neutral, 0.0),
+      (SocdMode::LastWins, 1.0),
+      (SocdMode::FirstWins, -1.0),
+    ] {
+      let config = DevcadeInputConfig { socd, ..default() };
+      let mut app = keyboard_app(config, |input| {
+        (
+          input.stick(Player::P1).x,
+          input.pressed(Player::P1, Button::StickLeft),
+          input.pressed(Player::P1, Button::StickRight),
+        )
+      });
+      let stick = |app: &mut App| update_and_read::<(f32, bool, bool)>(app);
+
+      // Left, then right as well
+      press_key(&mut app, KeyCode::V);
+      assert_eq!(stick(&mut app).0, -1.0);
+      press_key(&mut app, KeyCode::N);
+      for _ in 0..3 {
+        let (x, left, right) = stick(&mut app);
+        assert_eq!(x, both_held, "{socd:?}");
+        assert_eq!((left, right), (x < 0.0, x > 0.0), "{socd:?}");
+      }
+
+      // Letting go of left leaves right
+      release_key(&mut app, KeyCode::V);
+      assert_eq!(stick(&mut app).0, 1.0);
+
+      // Both at once cancel out
+      release_key(&mut app, KeyCode::N);
+      app.update();
+      press_key(&mut app, KeyCode::V);
+      press_key(&mut app, KeyCode::N);
+      assert_eq!(stick(&mut app).0, 0.0, "{socd:?}");
+    }
+  }
+
+  #[test]
+  fn reconnecting_in_time_keeps_buttons_held() {
+    let (mut app, gamepad) = controls_app(DevcadeInputConfig::default(), |input| {
+      let pressed = input.pressed(Player::P1, Button::A1);
+      let changed =
+        input.just_pressed(Player::P1, Button::A1) || input.just_released(Player::P1, Button::A1);
+      (pressed, changed)
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    press_gamepad_button(&mut app, gamepad, GamepadButtonType::West);
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, true));
+
+    // The cable comes loose with A1 held, and is plugged back in
+    disconnect_gamepad(&mut app, gamepad);
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, false));
+    connect_gamepad(&mut app, gamepad);
+    press_gamepad_button(&mut app, gamepad, GamepadButtonType::West);
+    for _ in 0..2 {
+      assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, false));
+    }
+
+    // This time it stays out, so A1 is released once the grace runs out
+    disconnect_gamepad(&mut app, gamepad);
+    for _ in 0..9 {
+      assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, false));
+    }
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (false, true));
+  }
+
+  #[test]
+  fn gamepad_settle_keeps_assignments_through_blips() {
+    let config = DevcadeInputConfig {
+      gamepad_settle: Duration::from_millis(500),
+      ..default()
+    };
+    let mut app = keyboard_app(config, |input| {
+      let name = |player| input.gamepad_name(player).map(str::to_owned);
+      (name(Player::P1), name(Player::P2))
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    type Names = (Option<String>, Option<String>);
+    let (pad0, pad1) = (Gamepad::new(0), Gamepad::new(1));
+    connect_gamepad(&mut app, pad0);
+    connect_gamepad(&mut app, pad1);
+    for _ in 0..5 {
+      app.update();
+    }
+    let assigned = (Some("Gamepad 0".to_owned()), Some("Gamepad 1".to_owned()));
+    assert_eq!(update_and_read::<Names>(&mut app), assigned);
+
+    // Pad 0 drops out and comes back. P2 doesn't get moved onto P1's pad
+    disconnect_gamepad(&mut app, pad0);
+    let (_, p2) = &app.world.resource::<Read<Names>>().0;
+    assert_eq!(p2, &assigned.1);
+    connect_gamepad(&mut app, pad0);
+    for _ in 0..10 {
+      assert_eq!(update_and_read::<Names>(&mut app), assigned);
+    }
+
+    // Pad 0 stays gone, so P2's pad moves to P1 once things settle
+    disconnect_gamepad(&mut app, pad0);
+    for _ in 0..5 {
+      app.update();
+    }
+    assert_eq!(
+      update_and_read::<Names>(&mut app),
+      (Some("Gamepad 1".to_owned()), None)
+    );
+  }
+}
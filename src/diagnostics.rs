@@ -0,0 +1,155 @@
+use crate::BackendClientResource;
+use async_compat::Compat;
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use std::time::{Duration, Instant};
+
+/// Reports the health of the Devcade backend through bevy's
+/// [`DiagnosticsStore`](bevy::diagnostic::DiagnosticsStore), so it shows up
+/// in diagnostic overlays alongside FPS
+///
+/// Pings the backend every [`interval`](BackendDiagnosticsPlugin::interval)
+/// and records how long it took to answer as
+/// [`BACKEND_PING_MS`](BackendDiagnosticsPlugin::BACKEND_PING_MS), and records
+/// how many requests are waiting for an answer every frame as
+/// [`BACKEND_PENDING`](BackendDiagnosticsPlugin::BACKEND_PENDING). Failed pings
+/// aren't recorded.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::BackendDiagnosticsPlugin;
+/// use std::time::Duration;
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///   MinimalPlugins,
+///   BackendDiagnosticsPlugin {
+///     interval: Duration::from_secs(5),
+///   },
+/// ));
+/// ```
+pub struct BackendDiagnosticsPlugin {
+  /// How often the backend is pinged. Defaults to one second
+  pub interval: Duration,
+}
+
+impl BackendDiagnosticsPlugin {
+  /// How long the backend took to answer the last ping, in milliseconds
+  pub const BACKEND_PING_MS: DiagnosticId =
+    DiagnosticId::from_u128(0x5f1c_2d0e_7a3b_4c69_9e8d_1b2a_3c4d_5e6f);
+  /// How many requests to the backend are waiting for an answer
+  pub const BACKEND_PENDING: DiagnosticId =
+    DiagnosticId::from_u128(0x5f1c_2d0e_7a3b_4c69_9e8d_1b2a_3c4d_5e70);
+}
+
+impl Default for BackendDiagnosticsPlugin {
+  fn default() -> Self {
+    Self {
+      interval: Duration::from_secs(1),
+    }
+  }
+}
+
+impl Plugin for BackendDiagnosticsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_diagnostic(
+        Diagnostic::new(Self::BACKEND_PING_MS, "backend_ping", 20).with_suffix("ms"),
+      )
+      .register_diagnostic(Diagnostic::new(
+        Self::BACKEND_PENDING,
+        "backend_pending",
+        20,
+      ))
+      .init_resource::<BackendClientResource>()
+      .insert_resource(BackendPing {
+        timer: Timer::new(self.interval, TimerMode::Repeating),
+        task: None,
+      })
+      .add_systems(Update, measure_backend);
+  }
+}
+
+#[derive(Resource)]
+struct BackendPing {
+  timer: Timer,
+  /// The ping in progress, which gives how long it took if it succeeded
+  task: Option<Task<Option<Duration>>>,
+}
+
+fn measure_backend(
+  time: Res<Time>,
+  client: Res<BackendClientResource>,
+  mut ping: ResMut<BackendPing>,
+  mut diagnostics: Diagnostics,
+) {
+  diagnostics.add_measurement(BackendDiagnosticsPlugin::BACKEND_PENDING, || {
+    client.pending_requests() as f64
+  });
+
+  let ping = &mut *ping;
+  if let Some(task) = &mut ping.task {
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+      return;
+    };
+    ping.task = None;
+    if let Some(latency) = result {
+      diagnostics.add_measurement(BackendDiagnosticsPlugin::BACKEND_PING_MS, || {
+        latency.as_secs_f64() * 1000.0
+      });
+    }
+  }
+  if ping.timer.tick(time.delta()).just_finished() {
+    let client = client.0.clone();
+    ping.task = Some(AsyncComputeTaskPool::get().spawn(Compat::new(async move {
+      let started = Instant::now();
+      match client.ping().await {
+        Ok(_) => Some(started.elapsed()),
+        Err(err) => {
+          log::debug!("Couldn't ping the backend for diagnostics. {err}");
+          None
+        }
+      }
+    })));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_backend::TestBackend;
+  use bevy::diagnostic::DiagnosticsStore;
+  use devcade_onboard_types::ResponseBody;
+
+  #[test]
+  fn records_ping_and_pending_requests() {
+    let backend = TestBackend::respond(|_| ResponseBody::Pong);
+    let mut app = App::new();
+    app
+      .add_plugins((
+        MinimalPlugins,
+        BackendDiagnosticsPlugin {
+          interval: Duration::from_millis(10),
+        },
+      ))
+      .insert_resource(BackendClientResource::new(backend.client()));
+    for _ in 0..100 {
+      app.update();
+      std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let store = app.world.resource::<DiagnosticsStore>();
+    let ping = store
+      .get(BackendDiagnosticsPlugin::BACKEND_PING_MS)
+      .unwrap();
+    assert!(ping.history_len() > 1);
+    assert!(ping.value().unwrap() >= 0.0);
+    let pending = store
+      .get(BackendDiagnosticsPlugin::BACKEND_PENDING)
+      .unwrap();
+    assert_eq!(pending.history_len(), 20);
+  }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+/// Whether the game is running on a Devcade cabinet or on a developer's
+/// machine, for features that only make sense in one or the other
+///
+/// # Examples
+/// ```
+/// use devcaders::DevcadeEnv;
+///
+/// let socket = std::env::temp_dir().join("devcaders-env.sock");
+/// std::fs::write(&socket, "").unwrap();
+///
+/// std::env::remove_var("DEVCADE_ONBOARD_PATH");
+/// assert_eq!(DevcadeEnv::detect(), DevcadeEnv::Dev);
+///
+/// std::env::set_var("DEVCADE_ONBOARD_PATH", &socket);
+/// assert_eq!(DevcadeEnv::detect(), DevcadeEnv::Cabinet);
+/// assert!(DevcadeEnv::detect().is_cabinet());
+///
+/// // Set, but nothing is there
+/// std::fs::remove_file(&socket).unwrap();
+/// assert_eq!(DevcadeEnv::detect(), DevcadeEnv::Dev);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevcadeEnv {
+  /// Launched by the cabinet's onboard
+  Cabinet,
+  /// Anywhere else
+  Dev,
+}
+
+impl DevcadeEnv {
+  /// Works out where the game is running
+  ///
+  /// It's a cabinet if the `DEVCADE_ONBOARD_PATH` environment variable is
+  /// set and the onboard's socket exists at that path. Pointing the variable
+  /// at a backend on a dev machine, like a fake one in tests, makes it look
+  /// like a cabinet too.
+  pub fn detect() -> Self {
+    match std::env::var_os("DEVCADE_ONBOARD_PATH") {
+      Some(path) if Path::new(&path).exists() => Self::Cabinet,
+      _ => Self::Dev,
+    }
+  }
+
+  /// Returns true on a cabinet
+  pub fn is_cabinet(self) -> bool {
+    self == Self::Cabinet
+  }
+}
@@ -0,0 +1,173 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadInfo};
+use bevy::prelude::*;
+
+/// Connects `gamepad` to `app` as a controller named `Gamepad {id}`, for
+/// tests that drive [`DevcadeControls`](crate::DevcadeControls) through real
+/// controller input
+///
+/// Updates `app` once, since Bevy only registers the controller when it
+/// handles the connection, and clears its buttons when it does. Press
+/// buttons after this, not before.
+///
+/// # Examples
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{
+///   connect_gamepad, press_gamepad_button, release_gamepad_button, set_axis, Button,
+///   DevcadeControls, Player,
+/// };
+///
+/// #[derive(Resource, Default)]
+/// struct Pressed(Vec<Button>);
+///
+/// fn input_system(input: DevcadeControls, mut pressed: ResMut<Pressed>) {
+///   pressed.0 = input.pressed_buttons(Player::P1).collect();
+/// }
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .init_resource::<Pressed>()
+///   .add_systems(Update, input_system);
+/// let gamepad = Gamepad::new(0);
+/// connect_gamepad(&mut app, gamepad);
+///
+/// set_axis(&mut app, gamepad, GamepadAxisType::LeftStickX, 1.0);
+/// app.update();
+/// assert_eq!(app.world.resource::<Pressed>().0, [Button::StickRight]);
+///
+/// set_axis(&mut app, gamepad, GamepadAxisType::LeftStickX, 0.0);
+/// set_axis(&mut app, gamepad, GamepadAxisType::LeftStickY, -1.0);
+/// press_gamepad_button(&mut app, gamepad, GamepadButtonType::West);
+/// app.update();
+/// let mut pressed = app.world.resource::<Pressed>().0.clone();
+/// pressed.sort_by_key(Button::index);
+/// assert_eq!(pressed, [Button::A1, Button::StickDown]);
+///
+/// set_axis(&mut app, gamepad, GamepadAxisType::LeftStickY, 0.0);
+/// release_gamepad_button(&mut app, gamepad, GamepadButtonType::West);
+/// app.update();
+/// assert_eq!(app.world.resource::<Pressed>().0, []);
+/// ```
+pub fn connect_gamepad(app: &mut App, gamepad: Gamepad) {
+  let info = GamepadInfo {
+    name: format!("Gamepad {}", gamepad.id),
+  };
+  app.world.send_event(GamepadConnectionEvent::new(
+    gamepad,
+    GamepadConnection::Connected(info),
+  ));
+  app.update();
+}
+
+/// Holds down `button_type` on `gamepad` until it's
+/// [released](release_gamepad_button), as if the controller reported it. See
+/// [`connect_gamepad`]
+pub fn press_gamepad_button(app: &mut App, gamepad: Gamepad, button_type: GamepadButtonType) {
+  set_gamepad_button(app, GamepadButton::new(gamepad, button_type), true);
+}
+
+/// Lets go of `button_type` on `gamepad`. See [`connect_gamepad`]
+pub fn release_gamepad_button(app: &mut App, gamepad: Gamepad, button_type: GamepadButtonType) {
+  set_gamepad_button(app, GamepadButton::new(gamepad, button_type), false);
+}
+
+fn set_gamepad_button(app: &mut App, button: GamepadButton, pressed: bool) {
+  let mut inputs = app.world.resource_mut::<Input<GamepadButton>>();
+  if pressed {
+    inputs.press(button);
+  } else {
+    inputs.release(button);
+  }
+  // How far it's pushed, for analog buttons like triggers
+  let value = if pressed { 1.0 } else { 0.0 };
+  app
+    .world
+    .resource_mut::<Axis<GamepadButton>>()
+    .set(button, value);
+}
+
+/// Moves `axis_type` on `gamepad` to `value`, between -1 and 1, until it's
+/// set again. See [`connect_gamepad`]
+pub fn set_axis(app: &mut App, gamepad: Gamepad, axis_type: GamepadAxisType, value: f32) {
+  app
+    .world
+    .resource_mut::<Axis<GamepadAxis>>()
+    .set(GamepadAxis::new(gamepad, axis_type), value);
+}
+
+/// The latest result of the `read` function given to [`controls_app`]
+#[cfg(test)]
+#[derive(Resource, Default)]
+pub(crate) struct Read<T>(pub(crate) T);
+
+/// Creates an app with `config` and no gamepads, so input comes from the
+/// keyboard, that stores what `read` makes of
+/// [`DevcadeControls`](crate::DevcadeControls) in [`Read`] every frame
+#[cfg(test)]
+pub(crate) fn keyboard_app<T: Default + Send + Sync + 'static>(
+  config: crate::DevcadeInputConfig,
+  read: impl Fn(&mut crate::DevcadeControls) -> T + Send + Sync + 'static,
+) -> App {
+  let mut app = App::new();
+  app
+    .add_plugins((MinimalPlugins, bevy::input::InputPlugin))
+    .insert_resource(config)
+    .init_resource::<Read<T>>()
+    .add_systems(
+      Update,
+      move |mut input: crate::DevcadeControls, mut latest: ResMut<Read<T>>| {
+        latest.0 = read(&mut input);
+      },
+    );
+  app
+}
+
+/// Like [`keyboard_app`], but with one gamepad connected
+#[cfg(test)]
+pub(crate) fn controls_app<T: Default + Send + Sync + 'static>(
+  config: crate::DevcadeInputConfig,
+  read: impl Fn(&mut crate::DevcadeControls) -> T + Send + Sync + 'static,
+) -> (App, Gamepad) {
+  let mut app = keyboard_app(config, read);
+  let gamepad = Gamepad::new(0);
+  connect_gamepad(&mut app, gamepad);
+  (app, gamepad)
+}
+
+/// Unplugs `gamepad`, the opposite of [`connect_gamepad`]
+#[cfg(test)]
+pub(crate) fn disconnect_gamepad(app: &mut App, gamepad: Gamepad) {
+  app.world.send_event(GamepadConnectionEvent::new(
+    gamepad,
+    GamepadConnection::Disconnected,
+  ));
+  app.update();
+}
+
+/// Presses `key` on the keyboard, leaving it held until it's released
+#[cfg(test)]
+pub(crate) fn press_key(app: &mut App, key: KeyCode) {
+  app.world.resource_mut::<Input<KeyCode>>().press(key);
+}
+
+/// Releases `key` on the keyboard
+#[cfg(test)]
+pub(crate) fn release_key(app: &mut App, key: KeyCode) {
+  app.world.resource_mut::<Input<KeyCode>>().release(key);
+}
+
+/// Updates `app` and returns what its [`controls_app`] `read` function made
+/// of the frame
+#[cfg(test)]
+pub(crate) fn update_and_read<T: Clone + Send + Sync + 'static>(app: &mut App) -> T {
+  app.update();
+  app.world.resource::<Read<T>>().0.clone()
+}
+
+/// Moves `gamepad`'s left stick to `(x, y)`
+#[cfg(test)]
+pub(crate) fn set_stick(app: &mut App, gamepad: Gamepad, x: f32, y: f32) {
+  set_axis(app, gamepad, GamepadAxisType::LeftStickX, x);
+  set_axis(app, gamepad, GamepadAxisType::LeftStickY, y);
+}
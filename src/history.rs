@@ -0,0 +1,27 @@
+use crate::InputSnapshot;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Ring buffer of the last few frames of input
+#[derive(Default, Clone)]
+pub(crate) struct InputHistory {
+  /// Shared with the [`DevcadeControls`](crate::DevcadeControls) handed out
+  /// last frame, which is gone by the time the next frame is recorded, so
+  /// recording doesn't have to copy the buffer
+  snapshots: Arc<VecDeque<InputSnapshot>>,
+}
+
+impl InputHistory {
+  /// Records a frame, dropping the oldest ones past `capacity`
+  pub(crate) fn record(&mut self, capacity: usize, snapshot: InputSnapshot) {
+    let snapshots = Arc::make_mut(&mut self.snapshots);
+    snapshots.push_back(snapshot);
+    while snapshots.len() > capacity {
+      snapshots.pop_front();
+    }
+  }
+
+  pub(crate) fn snapshots(&self) -> Arc<VecDeque<InputSnapshot>> {
+    self.snapshots.clone()
+  }
+}
@@ -0,0 +1,235 @@
+use crate::{DevcadeControls, DevcadeInputSet, InputSnapshot, Player};
+use bevy::{app::AppExit, prelude::*};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often buffered lines are written out to the log
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Writes every frame of input to a file, for reproducing bugs players run
+/// into on a cabinet
+///
+/// Each frame is one line: the frame number, milliseconds since startup, and
+/// then each player's held buttons and stick position.
+///
+/// ```text
+/// 42 700 p1=A1+StickRight 1.000,0.000 p2=- 0.000,0.000
+/// ```
+///
+/// Before a frame would take the file past
+/// [`max_bytes`](InputLogPlugin::max_bytes), it's moved to the same path with
+/// `.1` appended, replacing the last one, and a new
+/// file is started. So at most twice `max_bytes` is kept on disk. If the file
+/// can't be written, the error is logged and logging stops.
+///
+/// Lines are buffered and written out every second, when the log is rotated,
+/// and when the app exits, so logging doesn't cost a write every frame. A
+/// crash can lose the last second or so.
+///
+/// # Examples
+/// ```
+/// use bevy::{app::AppExit, input::InputPlugin, prelude::*};
+/// use devcaders::InputLogPlugin;
+///
+/// let path = std::env::temp_dir().join("devcaders-input-log.txt");
+/// let rotated = std::env::temp_dir().join("devcaders-input-log.txt.1");
+/// let _ = std::fs::remove_file(&path);
+/// let _ = std::fs::remove_file(&rotated);
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///   MinimalPlugins,
+///   InputPlugin,
+///   InputLogPlugin {
+///     path: path.clone(),
+///     max_bytes: 200,
+///   },
+/// ));
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Q);
+/// app.update();
+/// app.update();
+/// // Nothing's written out until a second has passed or the app exits
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+/// app.world.send_event(AppExit);
+/// app.update();
+///
+/// let log = std::fs::read_to_string(&path).unwrap();
+/// let frames: Vec<_> = log.lines().map(|line| line.split(' ').next().unwrap()).collect();
+/// assert_eq!(frames, ["0", "1", "2"]);
+/// assert!(log.lines().all(|line| line.contains(" p1=A1 ")));
+/// assert!(!rotated.exists());
+///
+/// // Going over the limit moves the log aside
+/// for _ in 0..9 {
+///   app.update();
+/// }
+/// drop(app);
+/// let old = std::fs::read_to_string(&rotated).unwrap();
+/// let newest = std::fs::read_to_string(&path).unwrap();
+/// assert!(old.len() <= 200 && newest.len() <= 200);
+/// let frame = |line: &str| line.split(' ').next().unwrap().parse::<u64>().unwrap();
+/// let first_new = frame(newest.lines().next().unwrap());
+/// assert_eq!(frame(old.lines().last().unwrap()) + 1, first_new);
+/// assert_eq!(frame(newest.lines().last().unwrap()), 11);
+///
+/// // A full log left from last time is moved aside before anything's added
+/// let full = "x".repeat(199) + "\n";
+/// std::fs::write(&path, &full).unwrap();
+/// let mut app = App::new();
+/// app.add_plugins((
+///   MinimalPlugins,
+///   InputPlugin,
+///   InputLogPlugin {
+///     path: path.clone(),
+///     max_bytes: 200,
+///   },
+/// ));
+/// app.update();
+/// drop(app);
+/// assert_eq!(std::fs::read_to_string(&rotated).unwrap(), full);
+/// assert!(std::fs::read_to_string(&path).unwrap().starts_with("0 "));
+/// # std::fs::remove_file(&path).unwrap();
+/// # std::fs::remove_file(&rotated).unwrap();
+/// ```
+pub struct InputLogPlugin {
+  /// Where to write the log
+  pub path: PathBuf,
+  /// How big the log can get before it's rotated. Defaults to 1MiB
+  pub max_bytes: u64,
+}
+
+impl InputLogPlugin {
+  /// Logs input to `path`, rotating every megabyte
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      max_bytes: 1 << 20,
+    }
+  }
+}
+
+impl Plugin for InputLogPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .insert_resource(InputLog {
+        path: self.path.clone(),
+        max_bytes: self.max_bytes,
+        file: None,
+        written: 0,
+        flushed_at: Duration::ZERO,
+        failed: false,
+      })
+      .add_systems(PreUpdate, write_input_log.after(DevcadeInputSet))
+      .add_systems(Last, flush_input_log_on_exit);
+  }
+}
+
+#[derive(Resource)]
+struct InputLog {
+  path: PathBuf,
+  max_bytes: u64,
+  /// The open log, once the first frame has been written
+  file: Option<BufWriter<File>>,
+  /// Bytes in the open log, including ones not written out yet
+  written: u64,
+  /// When buffered lines were last written out
+  flushed_at: Duration,
+  /// Set after an error, so it's only logged once
+  failed: bool,
+}
+
+impl InputLog {
+  fn write(&mut self, line: &str, now: Duration) -> io::Result<()> {
+    if self.file.is_none() {
+      self.open()?;
+    }
+    if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+      self.flush(now)?;
+      self.file = None;
+      fs::rename(&self.path, rotated_path(&self.path))?;
+      self.open()?;
+    }
+    if let Some(file) = &mut self.file {
+      file.write_all(line.as_bytes())?;
+      self.written += line.len() as u64;
+    }
+    if now.saturating_sub(self.flushed_at) >= FLUSH_INTERVAL {
+      self.flush(now)?;
+    }
+    Ok(())
+  }
+
+  fn open(&mut self) -> io::Result<()> {
+    let file = File::options().create(true).append(true).open(&self.path)?;
+    self.written = file.metadata()?.len();
+    self.file = Some(BufWriter::new(file));
+    Ok(())
+  }
+
+  fn flush(&mut self, now: Duration) -> io::Result<()> {
+    self.flushed_at = now;
+    match &mut self.file {
+      Some(file) => file.flush(),
+      None => Ok(()),
+    }
+  }
+}
+
+/// `path` with `.1` on the end
+fn rotated_path(path: &Path) -> PathBuf {
+  let mut rotated = path.as_os_str().to_owned();
+  rotated.push(".1");
+  rotated.into()
+}
+
+/// One line of the log for `snapshot`
+fn log_line(snapshot: &InputSnapshot) -> String {
+  let mut line = format!("{} {}", snapshot.frame, snapshot.time.as_millis());
+  for player in enum_iterator::all::<Player>() {
+    let pressed = snapshot.pressed(player);
+    let buttons = if pressed.is_empty() {
+      "-".to_owned()
+    } else {
+      let names: Vec<_> = pressed.iter().map(|button| format!("{button:?}")).collect();
+      names.join("+")
+    };
+    let stick = snapshot.stick(player);
+    let label = match player {
+      Player::P1 => "p1",
+      Player::P2 => "p2",
+    };
+    let _ = write!(line, " {label}={buttons} {:.3},{:.3}", stick.x, stick.y);
+  }
+  line.push('\n');
+  line
+}
+
+fn write_input_log(input: DevcadeControls, mut log: ResMut<InputLog>) {
+  if log.failed {
+    return;
+  }
+  let snapshot = input.snapshot();
+  if let Err(err) = log.write(&log_line(&snapshot), snapshot.time) {
+    log::error!(
+      "Couldn't write input log to {:?}, stopping. {err}",
+      log.path
+    );
+    log.failed = true;
+  }
+}
+
+fn flush_input_log_on_exit(
+  time: Res<Time>,
+  mut exits: EventReader<AppExit>,
+  mut log: ResMut<InputLog>,
+) {
+  if exits.read().count() == 0 || log.failed {
+    return;
+  }
+  if let Err(err) = log.flush(time.elapsed()) {
+    log::error!("Couldn't write input log to {:?}. {err}", log.path);
+  }
+}
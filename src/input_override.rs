@@ -0,0 +1,84 @@
+use crate::{Button, Player};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Simulated button presses that [`DevcadeControls`](crate::DevcadeControls)
+/// reports alongside live input, e.g. for tutorials that demonstrate a move
+///
+/// Overrides are OR'd with live input: an overridden button reads as pressed
+/// no matter what the physical controls are doing, and a button that isn't
+/// overridden reads from the controls as usual. Overrides can't force a
+/// button to read as released.
+///
+/// A press takes effect in [`DevcadeInputSet`](crate::DevcadeInputSet) on the
+/// next frame and lasts for the requested number of frames. This requires
+/// [`DevcadePlugin`](crate::DevcadePlugin) to count frames down.
+///
+/// # Examples
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{Button, DevcadeControls, DevcadePlugin, InputOverride, Player};
+///
+/// #[derive(Resource, Default)]
+/// struct Jumping(bool);
+///
+/// fn jump_system(input: DevcadeControls, mut jumping: ResMut<Jumping>) {
+///   jumping.0 = input.pressed(Player::P1, Button::A1);
+/// }
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+///   .init_resource::<Jumping>()
+///   .add_systems(Update, jump_system);
+///
+/// app
+///   .world
+///   .resource_mut::<InputOverride>()
+///   .press_for_frames(Player::P1, Button::A1, 2);
+/// app.update();
+/// assert!(app.world.resource::<Jumping>().0);
+/// app.update();
+/// assert!(app.world.resource::<Jumping>().0);
+/// app.update();
+/// assert!(!app.world.resource::<Jumping>().0);
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct InputOverride {
+  pending: Vec<(Player, Button, u32)>,
+  active: HashMap<(Player, Button), u32>,
+}
+
+impl InputOverride {
+  /// Reports `button` as pressed by `player` for the next `frames` frames
+  pub fn press_for_frames(&mut self, player: Player, button: Button, frames: u32) {
+    self.pending.push((player, button, frames));
+  }
+
+  /// Returns true if `button` is currently being pressed by an override
+  pub fn pressed(&self, player: Player, button: Button) -> bool {
+    self.active.contains_key(&(player, button))
+  }
+
+  /// Releases every override, including ones that haven't taken effect yet
+  pub fn clear(&mut self) {
+    self.pending.clear();
+    self.active.clear();
+  }
+}
+
+/// Counts down active overrides and starts any that were requested last frame
+pub(crate) fn tick_input_overrides(mut overrides: ResMut<InputOverride>) {
+  let overrides = &mut *overrides;
+  overrides.active.retain(|_, frames| {
+    *frames -= 1;
+    *frames > 0
+  });
+  for (player, button, frames) in overrides.pending.drain(..) {
+    if frames == 0 {
+      continue;
+    }
+    let remaining = overrides.active.entry((player, button)).or_default();
+    *remaining = frames.max(*remaining);
+  }
+}
@@ -2,43 +2,146 @@
 //!
 //! # Input Handling
 //! See [The example for `DevcadeControls`](DevcadeControls#examples)
+//!
+//! Add [`DevcadePlugin`] to your app to enable extras like [`InputOverride`]
+//!
+//! # Backend
+//! The `backend` feature, on by default, adds `BackendClient` and the NFC
+//! components for talking to the Devcade backend. Games that only need input
+//! can turn it off to avoid pulling in tokio.
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 use async_compat::Compat;
+use bevy::app::AppExit;
 use bevy::ecs::component::Tick;
 use bevy::ecs::system::{SystemMeta, SystemParam};
 use bevy::ecs::world::unsafe_world_cell::UnsafeWorldCell;
+use bevy::input::InputSystem;
 use bevy::prelude::*;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 use bevy::tasks::{AsyncComputeTaskPool, Task};
 pub use devcade_onboard_types;
-use devcade_onboard_types::{Map, Player as BackendPlayer, RequestBody, ResponseBody, Value};
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+use devcade_onboard_types::{Player as BackendPlayer, RequestBody, ResponseBody};
 use enum_iterator::Sequence;
-use futures_lite::future;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+use futures_lite::{future, stream};
+use futures_lite::{Stream, StreamExt};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 use std::ops::Deref;
+use std::sync::Arc;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 use std::sync::OnceLock;
+use std::time::Duration;
 
-#[cfg(not(target_os = "windows"))]
+mod bindings;
+mod capture;
+mod chord;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 mod client;
-#[cfg(not(target_os = "windows"))]
-pub use client::{BackendClient, RequestError};
+mod config;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+mod diagnostics;
+mod env;
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+mod fake_gamepad;
+#[cfg(feature = "debug-input-history")]
+mod history;
+mod input_log;
+mod input_override;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+mod nfc_poller;
+mod raw;
+#[cfg(feature = "testing")]
+mod replay;
+mod resolver;
+mod snapshot;
+#[cfg(all(test, feature = "backend", not(target_os = "windows")))]
+mod test_backend;
+#[cfg(all(feature = "testing", not(target_os = "windows")))]
+mod testing;
+mod user;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+mod warmup;
+pub use bindings::KeyboardBindings;
+pub use capture::{InputCaptureMode, InputPaused, InputReset, PlayerInputEnabled};
+pub use chord::{exit_on_chord, Chord, ChordEntry, ExitChord, PressMode};
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub use client::{
+  expected_response, supported_requests, BackendClient, ClientStats, CompletionHook, Deadline,
+  FromResponseBody, IntoRequestBody, PendingRequest, Pong, RateLimitMode, RequestBuilder,
+  RequestError, ResponseBodyExt, RetryPolicy,
+};
+pub use config::{DevcadeInputConfig, ResponseCurve, SocdMode, StickMode};
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub use diagnostics::BackendDiagnosticsPlugin;
+pub use env::DevcadeEnv;
+#[cfg(feature = "testing")]
+pub use fake_gamepad::{connect_gamepad, press_gamepad_button, release_gamepad_button, set_axis};
+pub use input_log::InputLogPlugin;
+pub use input_override::InputOverride;
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub use nfc_poller::NfcPoller;
+pub use raw::{RawButtonReading, RawGamepadReadout};
+#[cfg(feature = "testing")]
+pub use replay::InputReplay;
+pub use resolver::ButtonResolver;
+pub use snapshot::InputSnapshot;
+#[cfg(all(feature = "testing", not(target_os = "windows")))]
+pub use testing::{FakeBackendServer, FakeBackendServerBuilder};
+pub use user::{AssociationId, DevcadeUser};
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub use warmup::{BackendStatus, BackendWarmupPlugin};
 
 #[derive(SystemParam)]
 struct DevcadeControlsInner<'w> {
-  gamepads: Res<'w, Gamepads>,
-  button_inputs: Res<'w, Input<GamepadButton>>,
-  axes: Res<'w, Axis<GamepadAxis>>,
-  keyboard_input: Res<'w, Input<KeyCode>>,
+  gamepads: Option<Res<'w, Gamepads>>,
+  button_inputs: Option<Res<'w, Input<GamepadButton>>>,
+  button_axes: Option<Res<'w, Axis<GamepadButton>>>,
+  axes: Option<Res<'w, Axis<GamepadAxis>>>,
+  keyboard_input: Option<Res<'w, Input<KeyCode>>>,
+  keyboard_bindings: Res<'w, KeyboardBindings>,
+  input_override: Res<'w, InputOverride>,
+  config: Res<'w, DevcadeInputConfig>,
+  capture: Res<'w, InputCaptureMode>,
+  paused: Res<'w, InputPaused>,
+  enabled: Res<'w, PlayerInputEnabled>,
+  reset: Res<'w, InputReset>,
+  time: Res<'w, Time>,
+}
+
+/// Stands in for bevy's input resources in apps without the
+/// [`InputPlugin`](bevy::input::InputPlugin), so nothing reads as pressed
+#[derive(Default)]
+struct NoInput {
+  gamepads: Gamepads,
+  button_inputs: Input<GamepadButton>,
+  button_axes: Axis<GamepadButton>,
+  axes: Axis<GamepadAxis>,
+  keyboard_input: Input<KeyCode>,
 }
 
 /// [`SystemParam`] for devcade's control buttons
 ///
+/// Reads from bevy's input and [`Time`] resources, so your app needs the
+/// [`InputPlugin`](bevy::input::InputPlugin) and
+/// [`TimePlugin`](bevy::time::TimePlugin), both of which are part of
+/// `DefaultPlugins`. Without the `InputPlugin`, like in a headless
+/// simulation, no buttons are ever pressed.
+///
 /// # Examples
 /// Usage is simple, just add it as a parameter to one of your [`System`](bevy::ecs::system::System)s!
 /// ```
+/// use bevy::{app::AppExit, prelude::*};
 /// use devcaders::{Button, Player, DevcadeControls};
 ///
-/// fn input_system(button_inputs: DevcadeControls) {
+/// fn input_system(button_inputs: DevcadeControls, mut exit: EventWriter<AppExit>) {
 ///   // User is actively pressing Menu button
 ///   if button_inputs.pressed(Player::P1, Button::Menu) {
-///     std::process::exit(0);
+///     exit.send(AppExit);
 ///   }
 ///   let mut x_vector = 0;
 ///   // User pressed StickRight button
@@ -52,13 +155,55 @@ struct DevcadeControlsInner<'w> {
 /// }
 /// ```
 pub struct DevcadeControls {
-  p1: PlayerControlState,
-  p2: PlayerControlState,
+  /// How many frames the system had seen before this one
+  frame: u64,
+  last_change: Option<(Player, Button, bool, Duration)>,
+  /// Shared with the system's state like the input history is, so handing
+  /// them out doesn't copy the players' controller lists
+  p1: Arc<PlayerControlState>,
+  p2: Arc<PlayerControlState>,
+  now: Duration,
+  /// When the system last ran before this one
+  last_ran: Duration,
+  /// [`DevcadeInputConfig::stick_nav_delay`] and
+  /// [`DevcadeInputConfig::stick_nav_interval`]
+  stick_nav: (Duration, Duration),
+  /// [`DevcadeInputConfig::deadzone`]
+  deadzone: f32,
+  /// Whether input is hidden because of [`InputCaptureMode`]
+  capturing: bool,
+  /// Whether input is hidden because of [`InputPaused`]
+  paused: bool,
+  /// Which players' input is reported
+  enabled: PlayerInputEnabled,
+  /// Every connected controller and who it belongs to
+  gamepad_assignments: Arc<[GamepadAssignment]>,
+  #[cfg(feature = "debug-input-history")]
+  history: Arc<std::collections::VecDeque<InputSnapshot>>,
+}
+/// A connected controller, from [`DevcadeControls::gamepad_assignments`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GamepadAssignment {
+  /// The controller
+  pub gamepad: Gamepad,
+  /// The controller's name
+  pub name: String,
+  /// The player using it, if any
+  pub player: Option<Player>,
 }
+
 #[derive(Default, Clone)]
 struct ButtonState {
   pressed: bool,
   changed_this_frame: bool,
+  /// `pressed` as of the previous run
+  pressed_last_frame: bool,
+  /// When the raw input started disagreeing with `pressed`, for debouncing
+  candidate_since: Option<Duration>,
+  /// When the button was last pressed, if it ever has been
+  last_pressed_at: Option<Duration>,
+  /// When the button was pressed before that, most recent first
+  earlier_presses: [Option<Duration>; 3],
 }
 #[derive(Default, Clone)]
 struct PlayerControlState {
@@ -75,6 +220,24 @@ struct PlayerControlState {
   b2: ButtonState,
   b3: ButtonState,
   b4: ButtonState,
+  /// The player's controllers, usually one at most. See
+  /// [`DevcadeInputConfig::gamepads_per_player`]
+  gamepads: Vec<Gamepad>,
+  /// Name of the player's controller, if they have one
+  gamepad_name: Option<String>,
+  /// Position of the player's stick this frame
+  stick: Vec2,
+  /// Position of the player's right stick this frame
+  right_stick: Vec2,
+  /// Which keyboard stick directions were held, for [`SocdMode`]
+  socd: resolver::SocdState,
+  /// The player's controllers that disconnected, and when, while their input
+  /// is held waiting for them to come back. See
+  /// [`DevcadeInputConfig::reconnect_grace`]
+  lost_gamepads: Option<(Vec<Gamepad>, Duration)>,
+  /// Set on the frame lost controllers come back, when they still read as
+  /// released, so their state is taken quietly on the next one
+  reconnecting: bool,
 }
 
 impl PlayerControlState {
@@ -117,8 +280,28 @@ impl PlayerControlState {
 
 /// Underlying state of [`DevcadeControls`]
 pub struct ControlState<'w> {
-  p1: PlayerControlState,
-  p2: PlayerControlState,
+  p1: Arc<PlayerControlState>,
+  p2: Arc<PlayerControlState>,
+  /// Connected gamepads as of the last change, ordered by id
+  seen_gamepads: Vec<Gamepad>,
+  /// [`DevcadeControls::gamepad_assignments`], rebuilt only when controllers
+  /// connect, disconnect or are reassigned
+  gamepad_assignments: Arc<[GamepadAssignment]>,
+  /// When the connected gamepads last changed, if players haven't been
+  /// reassigned since
+  gamepads_changed_at: Option<Duration>,
+  /// How many times the system has run
+  frame: u64,
+  /// When the system last ran
+  last_ran: Duration,
+  /// The last button to be pressed or released, whether it was pressed, and
+  /// when
+  last_change: Option<(Player, Button, bool, Duration)>,
+  /// The [`InputReset`] generation this state was last reset for
+  reset_generation: u64,
+  #[cfg(feature = "debug-input-history")]
+  history: history::InputHistory,
+  no_input: NoInput,
   inner: <DevcadeControlsInner<'w> as SystemParam>::State,
 }
 
@@ -126,10 +309,28 @@ unsafe impl SystemParam for DevcadeControls {
   type State = ControlState<'static>;
   type Item<'w, 's> = DevcadeControls;
   fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+    world.init_resource::<KeyboardBindings>();
+    world.init_resource::<InputOverride>();
+    world.init_resource::<DevcadeInputConfig>();
+    world.init_resource::<InputCaptureMode>();
+    world.init_resource::<InputPaused>();
+    world.init_resource::<PlayerInputEnabled>();
+    world.init_resource::<InputReset>();
+    let reset_generation = world.resource::<InputReset>().generation();
     Self::State {
       inner: DevcadeControlsInner::init_state(world, system_meta),
-      p1: PlayerControlState::default(),
-      p2: PlayerControlState::default(),
+      p1: Default::default(),
+      p2: Default::default(),
+      seen_gamepads: Vec::new(),
+      gamepad_assignments: Arc::new([]),
+      gamepads_changed_at: None,
+      frame: 0,
+      last_ran: Duration::ZERO,
+      last_change: None,
+      reset_generation,
+      #[cfg(feature = "debug-input-history")]
+      history: Default::default(),
+      no_input: NoInput::default(),
     }
   }
   unsafe fn get_param<'w, 's>(
@@ -139,21 +340,195 @@ unsafe impl SystemParam for DevcadeControls {
     change_tick: Tick,
   ) -> Self::Item<'w, 's> {
     let inner = DevcadeControlsInner::get_param(&mut state.inner, system_meta, world, change_tick);
+    let now = inner.time.elapsed();
+    let resolver = inner.resolver(&state.no_input);
+
+    let connected_gamepads = resolver.connected_gamepads();
+    let gamepads_changed = connected_gamepads != state.seen_gamepads;
+    if gamepads_changed {
+      state.seen_gamepads = connected_gamepads;
+      state.gamepads_changed_at = Some(now);
+    }
+    let reassign_gamepads = state
+      .gamepads_changed_at
+      .is_some_and(|changed_at| now - changed_at >= inner.config.gamepad_settle);
+    if reassign_gamepads {
+      state.gamepads_changed_at = None;
+    }
+
+    let reset = inner.reset.generation() != state.reset_generation;
+    if reset {
+      state.reset_generation = inner.reset.generation();
+      state.last_change = None;
+      Arc::make_mut(&mut state.p1).lost_gamepads = None;
+      Arc::make_mut(&mut state.p2).lost_gamepads = None;
+    }
+
     for player in enum_iterator::all::<Player>() {
       let player_state = match player {
-        Player::P1 => &mut state.p1,
-        Player::P2 => &mut state.p2,
+        Player::P1 => Arc::make_mut(&mut state.p1),
+        Player::P2 => Arc::make_mut(&mut state.p2),
       };
+      if player_state.lost_gamepads.is_none() && !inner.config.reconnect_grace.is_zero() {
+        let lost: Vec<_> = player_state
+          .gamepads
+          .iter()
+          .filter(|gamepad| !state.seen_gamepads.contains(gamepad))
+          .copied()
+          .collect();
+        if !lost.is_empty() {
+          player_state.lost_gamepads = Some((lost, now));
+        }
+      }
+      if reassign_gamepads {
+        let per_player = inner.config.gamepads_per_player.max(1);
+        player_state.gamepads = state
+          .seen_gamepads
+          .iter()
+          .skip(player.index() * per_player)
+          .take(per_player)
+          .copied()
+          .collect();
+      }
+      let gamepad_name = player_state
+        .gamepads
+        .first()
+        .and_then(|&gamepad| resolver.gamepads.name(gamepad));
+      if player_state.gamepad_name.as_deref() != gamepad_name {
+        player_state.gamepad_name = gamepad_name.map(str::to_owned);
+      }
+      let reconnected = std::mem::take(&mut player_state.reconnecting);
+      if let Some((lost, since)) = &player_state.lost_gamepads {
+        let back = lost.iter().all(|gamepad| {
+          state.seen_gamepads.contains(gamepad) && player_state.gamepads.contains(gamepad)
+        });
+        let expired = now - *since >= inner.config.reconnect_grace;
+        if back || expired {
+          player_state.lost_gamepads = None;
+          player_state.reconnecting = back;
+        }
+        if !expired {
+          // Nothing's known until it's back and reporting, so hold
+          // everything as it was
+          for button in enum_iterator::all::<Button>() {
+            let button_state = player_state.get_state_for_mut(button);
+            button_state.pressed_last_frame = button_state.pressed;
+            button_state.changed_this_frame = false;
+            button_state.candidate_since = None;
+          }
+          continue;
+        }
+      }
+      player_state.stick =
+        resolver.stick_with_socd(player, &player_state.gamepads, &mut player_state.socd);
+      player_state.right_stick = resolver.right_stick_on(player, &player_state.gamepads);
       for button in enum_iterator::all::<Button>() {
+        let pressed = match AxisConfig::try_from(&button) {
+          Ok(axis_config) => axis_config.pressed(player_state.stick),
+          Err(_) => resolver.pressed_on(button, player, &player_state.gamepads),
+        };
         let button_state = player_state.get_state_for_mut(button);
-        let pressed = inner.pressed(button, player);
-        button_state.changed_this_frame = pressed != button_state.pressed;
-        button_state.pressed = pressed;
+        if reset {
+          *button_state = ButtonState {
+            pressed,
+            pressed_last_frame: pressed,
+            last_pressed_at: pressed.then_some(now),
+            ..default()
+          };
+          continue;
+        }
+        if reconnected {
+          *button_state = ButtonState {
+            pressed,
+            pressed_last_frame: pressed,
+            candidate_since: None,
+            changed_this_frame: false,
+            ..button_state.clone()
+          };
+          continue;
+        }
+        button_state.pressed_last_frame = button_state.pressed;
+        button_state.changed_this_frame = false;
+        if pressed == button_state.pressed {
+          button_state.candidate_since = None;
+          continue;
+        }
+        let since = *button_state.candidate_since.get_or_insert(now);
+        if now - since >= inner.config.debounce {
+          button_state.changed_this_frame = true;
+          button_state.pressed = pressed;
+          button_state.candidate_since = None;
+          state.last_change = Some((player, button, pressed, now));
+          if pressed {
+            let [newer, older, _] = button_state.earlier_presses;
+            button_state.earlier_presses = [button_state.last_pressed_at, newer, older];
+            button_state.last_pressed_at = Some(now);
+          }
+        }
       }
     }
+    if gamepads_changed || reassign_gamepads {
+      state.gamepad_assignments = state
+        .seen_gamepads
+        .iter()
+        .map(|&gamepad| GamepadAssignment {
+          gamepad,
+          name: resolver
+            .gamepads
+            .name(gamepad)
+            .unwrap_or_default()
+            .to_owned(),
+          player: enum_iterator::all::<Player>().find(|player| {
+            let player_state = match player {
+              Player::P1 => &state.p1,
+              Player::P2 => &state.p2,
+            };
+            player_state.gamepads.contains(&gamepad)
+          }),
+        })
+        .collect();
+    }
+    let frame = state.frame;
+    state.frame += 1;
+    let last_ran = std::mem::replace(&mut state.last_ran, now);
+    #[cfg(feature = "debug-input-history")]
+    {
+      let player_state = |player| match player {
+        Player::P1 => &state.p1,
+        Player::P2 => &state.p2,
+      };
+      let snapshot = InputSnapshot::new(
+        frame,
+        now,
+        |player| {
+          enum_iterator::all::<Button>()
+            .filter(|&button| player_state(player).get_state_for(button).pressed)
+            .collect()
+        },
+        |player| player_state(player).stick,
+      );
+      state
+        .history
+        .record(inner.config.input_history_len, snapshot);
+    }
     DevcadeControls {
+      frame,
+      last_change: state.last_change,
       p1: state.p1.clone(),
       p2: state.p2.clone(),
+      now,
+      last_ran,
+      stick_nav: (
+        inner.config.stick_nav_delay,
+        inner.config.stick_nav_interval,
+      ),
+      deadzone: inner.config.deadzone,
+      capturing: inner.capture.active,
+      paused: inner.paused.0,
+      enabled: *inner.enabled,
+      gamepad_assignments: state.gamepad_assignments.clone(),
+      #[cfg(feature = "debug-input-history")]
+      history: state.history.snapshots(),
     }
   }
 }
@@ -166,26 +541,726 @@ impl DevcadeControls {
     }
   }
 
+  /// Whether the player's input is hidden by [`InputCaptureMode`] or
+  /// [`PlayerInputEnabled`]
+  fn hidden(&self, player: Player) -> bool {
+    self.capturing || self.paused || !self.enabled.enabled(player)
+  }
+
+  fn get_player_mut(&mut self, player: Player) -> &mut PlayerControlState {
+    match player {
+      Player::P1 => Arc::make_mut(&mut self.p1),
+      Player::P2 => Arc::make_mut(&mut self.p2),
+    }
+  }
+
   /// Returns true when button began being pressed on this frame, false otherwise
   pub fn just_pressed(&self, player: Player, button: Button) -> bool {
-    let player = self.get_player(player);
-    let button_state = player.get_state_for(button);
-    button_state.pressed && button_state.changed_this_frame
+    let button_state = self.get_player(player).get_state_for(button);
+    !self.hidden(player) && button_state.pressed && button_state.changed_this_frame
   }
   /// Returns true when button began being unpressed on this frame, false otherwise
   pub fn just_released(&self, player: Player, button: Button) -> bool {
-    let player = self.get_player(player);
-    let button_state = player.get_state_for(button);
-    !button_state.pressed && button_state.changed_this_frame
+    let button_state = self.get_player(player).get_state_for(button);
+    !self.hidden(player) && !button_state.pressed && button_state.changed_this_frame
   }
   /// Returns true if the button is currently pressed
   pub fn pressed(&self, player: Player, button: Button) -> bool {
-    self.get_player(player).get_state_for(button).pressed
+    !self.hidden(player) && self.get_player(player).get_state_for(button).pressed
+  }
+  /// Returns true if the button is pressed and was already pressed before
+  /// this frame, so a hold can be told apart from a new press
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  ///
+  /// fn charge_system(input: DevcadeControls) {
+  ///   if input.held(Player::P1, Button::A1) {
+  ///     println!("Charging...");
+  ///   }
+  /// }
+  /// ```
+  pub fn held(&self, player: Player, button: Button) -> bool {
+    let button_state = self.get_player(player).get_state_for(button);
+    !self.hidden(player) && button_state.pressed && !button_state.changed_this_frame
+  }
+
+  /// Like [`just_pressed`](DevcadeControls::just_pressed), but a held stick
+  /// direction keeps repeating, like an arrow key, for scrolling through
+  /// menus
+  ///
+  /// Repeats start once the direction has been held for
+  /// [`stick_nav_delay`](DevcadeInputConfig::stick_nav_delay), and come every
+  /// [`stick_nav_interval`](DevcadeInputConfig::stick_nav_interval) after
+  /// that. At most one repeat is reported per frame. Buttons other than the
+  /// stick directions never repeat.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  ///
+  /// fn menu_cursor_system(input: DevcadeControls) {
+  ///   if input.stick_nav_just_pressed(Player::P1, Button::StickDown) {
+  ///     println!("Next item");
+  ///   }
+  /// }
+  /// ```
+  pub fn stick_nav_just_pressed(&self, player: Player, button: Button) -> bool {
+    if self.just_pressed(player, button) {
+      return true;
+    }
+    if AxisConfig::try_from(&button).is_err() || !self.held(player, button) {
+      return false;
+    }
+    let Some(held_for) = self.time_since_pressed(player, button) else {
+      return false;
+    };
+    let (delay, interval) = self.stick_nav;
+    if held_for < delay {
+      return false;
+    }
+    // Only reachable when nothing has validated the config
+    let interval = if interval.is_zero() {
+      DevcadeInputConfig::default().stick_nav_interval
+    } else {
+      interval
+    };
+    // How many repeats there have been after being held for `held_for`
+    let repeats = |held_for: Duration| match held_for.checked_sub(delay) {
+      Some(past_delay) => 1 + past_delay.as_nanos() / interval.as_nanos(),
+      None => 0,
+    };
+    let held_last_run = held_for.saturating_sub(self.now - self.last_ran);
+    repeats(held_for) > repeats(held_last_run)
+  }
+
+  /// Returns true if any button of either player was
+  /// [just pressed](DevcadeControls::just_pressed) or
+  /// [just released](DevcadeControls::just_released) on this frame. A cheap
+  /// check for skipping per-button work on frames where nothing changed.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn button_display_system(input: DevcadeControls) {
+  ///   if !input.any_changed_this_frame() {
+  ///     return;
+  ///   }
+  ///   let held: Vec<_> = input.pressed_buttons(Player::P1).collect();
+  ///   println!("P1 is holding {held:?}");
+  /// }
+  /// ```
+  pub fn any_changed_this_frame(&self) -> bool {
+    enum_iterator::all::<Player>().any(|player| {
+      let player_state = self.get_player(player);
+      !self.hidden(player)
+        && enum_iterator::all::<Button>()
+          .any(|button| player_state.get_state_for(button).changed_this_frame)
+    })
+  }
+
+  /// Returns true if the button was pressed on the previous frame, for
+  /// building transitions [`just_pressed`](DevcadeControls::just_pressed)
+  /// and [`just_released`](DevcadeControls::just_released) don't cover.
+  ///
+  /// Like the rest of `DevcadeControls`, the previous frame is the last time
+  /// this system ran. [`consume`](DevcadeControls::consume) doesn't affect it.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  ///
+  /// fn charge_system(input: DevcadeControls) {
+  ///   // Let go of a charged shot
+  ///   let pressed = input.pressed(Player::P1, Button::A1);
+  ///   if !pressed && input.was_pressed_last_frame(Player::P1, Button::A1) {
+  ///     println!("Fire!");
+  ///   }
+  /// }
+  /// ```
+  pub fn was_pressed_last_frame(&self, player: Player, button: Button) -> bool {
+    !self.hidden(player)
+      && self
+        .get_player(player)
+        .get_state_for(button)
+        .pressed_last_frame
+  }
+
+  /// Marks the button's [`just_pressed`](DevcadeControls::just_pressed) or
+  /// [`just_released`](DevcadeControls::just_released) as handled, so both
+  /// return false for the rest of this system run. [`pressed`](DevcadeControls::pressed)
+  /// is unaffected.
+  ///
+  /// Each system reads input separately, so this doesn't affect other
+  /// systems. A system that runs more than once in a frame, like one in
+  /// `FixedUpdate`, only sees a press as just pressed on its first run after
+  /// the press either way.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  ///
+  /// fn jump_system(mut input: DevcadeControls) {
+  ///   if input.just_pressed(Player::P1, Button::A1) {
+  ///     input.consume(Player::P1, Button::A1);
+  ///     println!("Jump!");
+  ///   }
+  ///   // The press is handled, so it can't also confirm a menu
+  ///   assert!(!input.just_pressed(Player::P1, Button::A1));
+  /// }
+  /// ```
+  pub fn consume(&mut self, player: Player, button: Button) {
+    self
+      .get_player_mut(player)
+      .get_state_for_mut(button)
+      .changed_this_frame = false;
+  }
+
+  /// Like [`DevcadeControls::consume`], for every button of both players
+  pub fn consume_all(&mut self) {
+    for player in enum_iterator::all::<Player>() {
+      for button in enum_iterator::all::<Button>() {
+        self.consume(player, button);
+      }
+    }
+  }
+
+  /// Returns true if any controllers are connected, whether or not they've
+  /// been assigned to players yet
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::DevcadeControls;
+  ///
+  /// fn controller_hint(input: DevcadeControls) {
+  ///   if !input.any_gamepad_connected() {
+  ///     println!("Plug in a controller to play");
+  ///   }
+  /// }
+  /// ```
+  pub fn any_gamepad_connected(&self) -> bool {
+    !self.gamepad_assignments.is_empty()
+  }
+
+  /// Returns every connected controller, ordered by id, along with the
+  /// player it's assigned to. Handy for a diagnostics screen
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::DevcadeControls;
+  ///
+  /// fn diagnostics_system(input: DevcadeControls) {
+  ///   for assignment in input.gamepad_assignments() {
+  ///     println!("{}: {:?}", assignment.name, assignment.player);
+  ///   }
+  /// }
+  /// ```
+  pub fn gamepad_assignments(&self) -> &[GamepadAssignment] {
+    &self.gamepad_assignments
+  }
+
+  /// Returns true while input is hidden from the game by [`InputCaptureMode`]
+  pub fn capturing(&self) -> bool {
+    self.capturing
+  }
+
+  /// Returns the button pressed on this frame while [`InputCaptureMode`] is
+  /// active, and who pressed it. If several were pressed at once, P1's come
+  /// first, in the order of [`Button`]'s variants
+  pub fn captured(&self) -> Option<(Player, Button)> {
+    if !self.capturing || self.paused {
+      return None;
+    }
+    enum_iterator::all::<Player>().find_map(|player| {
+      let player_state = self.get_player(player);
+      enum_iterator::all::<Button>()
+        .find(|&button| {
+          let button_state = player_state.get_state_for(button);
+          button_state.pressed && button_state.changed_this_frame
+        })
+        .map(|button| (player, button))
+    })
+  }
+
+  /// Returns the position of the player's stick this frame, from -1 to 1 on
+  /// each axis, with up and right positive
+  ///
+  /// The stick buttons are pressed exactly when the stick points their way,
+  /// so reading either gives the same answer. The exception is
+  /// [`DevcadeInputConfig::debounce`], which only delays the buttons. On the
+  /// keyboard, directions push the stick all the way and opposite directions
+  /// cancel out.
+  ///
+  /// # Examples
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{DevcadeControls, Player};
+  /// #[derive(Resource, Default)]
+  /// struct ShipPosition(Vec2);
+  ///
+  /// fn move_ship(input: DevcadeControls, time: Res<Time>, mut ship: ResMut<ShipPosition>) {
+  ///   ship.0 += input.stick(Player::P1) * 200.0 * time.delta_seconds();
+  /// }
+  /// ```
+  pub fn stick(&self, player: Player) -> Vec2 {
+    if self.hidden(player) {
+      return Vec2::ZERO;
+    }
+    self.get_player(player).stick
+  }
+
+  /// Returns the position of the player's right stick, from -1 to 1 on each
+  /// axis, for twin-stick games on controllers that have one. Zero on
+  /// controllers without one.
+  ///
+  /// Has the same deadzone and inversion as [`stick`](Self::stick), but
+  /// isn't affected by [`stick_curve`](DevcadeInputConfig::stick_curve) or
+  /// [`stick_mode`](DevcadeInputConfig::stick_mode), and the keyboard can't
+  /// move it.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn aim_system(input: DevcadeControls) {
+  ///   let aim = input.right_stick(Player::P1);
+  ///   if aim != Vec2::ZERO {
+  ///     println!("Shooting towards {aim}");
+  ///   }
+  /// }
+  /// ```
+  pub fn right_stick(&self, player: Player) -> Vec2 {
+    if self.hidden(player) {
+      return Vec2::ZERO;
+    }
+    self.get_player(player).right_stick
+  }
+
+  /// Returns how far the player's [stick](Self::stick) is pushed along
+  /// whichever axis it's pushed furthest on, from 0 at the edge of the
+  /// [deadzone](DevcadeInputConfig::deadzone) to 1 all the way out. Handy for
+  /// scrolling menus faster the further the stick is pushed.
+  ///
+  /// # Examples
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{DevcadeControls, Player};
+  /// #[derive(Resource, Default)]
+  /// struct MenuScroll(f32);
+  ///
+  /// fn scroll_menu(input: DevcadeControls, time: Res<Time>, mut scroll: ResMut<MenuScroll>) {
+  ///   // Up to 10 items a second, slower with a light push
+  ///   scroll.0 += input.stick_intensity(Player::P1) * 10.0 * time.delta_seconds();
+  /// }
+  /// ```
+  pub fn stick_intensity(&self, player: Player) -> f32 {
+    let stick = self.stick(player).abs();
+    let pushed = stick.x.max(stick.y);
+    if pushed <= self.deadzone {
+      return 0.0;
+    }
+    ((pushed - self.deadzone) / (1.0 - self.deadzone)).min(1.0)
+  }
+
+  /// Returns which of the 8 directions the player's [stick](Self::stick) is
+  /// pointing, or `None` if it's centered. Handy for menus, where diagonals
+  /// would otherwise need handling by hand.
+  ///
+  /// # Examples
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{DevcadeControls, Player};
+  /// fn show_direction(input: DevcadeControls) {
+  ///   if let Some(direction) = input.stick_direction(Player::P1) {
+  ///     println!("P1 is pointing {}", direction.glyph());
+  ///   }
+  /// }
+  /// ```
+  pub fn stick_direction(&self, player: Player) -> Option<Direction8> {
+    Direction8::from_stick(self.stick(player))
+  }
+
+  /// Returns the buttons held over the last
+  /// [`DevcadeInputConfig::input_history_len`] frames, oldest first, including
+  /// this one. Handy for dumping to a log when something goes wrong.
+  ///
+  /// Each system using `DevcadeControls` keeps its own history, counting
+  /// only the frames it ran on.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn dump_input(input: DevcadeControls) {
+  ///   for snapshot in input.input_history() {
+  ///     println!("{}: {:?}", snapshot.frame, snapshot.pressed(Player::P1));
+  ///   }
+  /// }
+  /// ```
+  #[cfg(feature = "debug-input-history")]
+  pub fn input_history(&self) -> impl Iterator<Item = &InputSnapshot> + '_ {
+    self.history.iter()
+  }
+
+  /// Describes what `player` put in over the last `within`, oldest first,
+  /// like a fighting game's input display: `"↓ ↘ → + A1"`
+  ///
+  /// Each entry is a frame where the stick moved to a new direction, shown
+  /// as a [glyph](Direction8::glyph), or buttons were pressed, joined with
+  /// `+`. Letting go of the stick isn't shown, but pushing the same way again
+  /// afterwards is. Built on [`DevcadeControls::input_history`], so it can
+  /// only look back as far as
+  /// [`DevcadeInputConfig::input_history_len`] frames.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  /// use std::time::Duration;
+  ///
+  /// fn training_mode_system(input: DevcadeControls) {
+  ///   println!("{}", input.input_history_display(Player::P1, Duration::from_secs(2)));
+  /// }
+  /// ```
+  #[cfg(feature = "debug-input-history")]
+  pub fn input_history_display(&self, player: Player, within: Duration) -> String {
+    let since = self.now.saturating_sub(within);
+    let mut entries = Vec::new();
+    let mut previous: Option<&InputSnapshot> = None;
+    for snapshot in self.history.iter() {
+      let direction = Direction8::from_stick(snapshot.stick(player));
+      let was_pressed =
+        |button| previous.is_some_and(|previous| previous.pressed(player).contains(&button));
+      let last_direction =
+        previous.and_then(|previous| Direction8::from_stick(previous.stick(player)));
+      let pressed: Vec<_> = snapshot
+        .pressed(player)
+        .iter()
+        .filter(|&&button| AxisConfig::try_from(&button).is_err() && !was_pressed(button))
+        .map(|button| format!("{button:?}"))
+        .collect();
+      previous = Some(snapshot);
+      if snapshot.time < since {
+        continue;
+      }
+      let mut entry = match direction {
+        Some(direction) if Some(direction) != last_direction => direction.glyph().to_string(),
+        _ => String::new(),
+      };
+      if !pressed.is_empty() {
+        if !entry.is_empty() {
+          entry.push_str(" + ");
+        }
+        entry.push_str(&pressed.join("+"));
+      }
+      if !entry.is_empty() {
+        entries.push(entry);
+      }
+    }
+    entries.join(" ")
+  }
+
+  /// Returns the button that was most recently pressed or released, whether
+  /// it was pressed, and how long ago. `None` until the first change.
+  ///
+  /// When several buttons change on the same frame, P2's come after P1's and
+  /// buttons come in [index](Button::index) order.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::DevcadeControls;
+  ///
+  /// fn idle_hint_system(input: DevcadeControls) {
+  ///   if let Some((player, button, pressed, ago)) = input.last_changed() {
+  ///     println!("{player:?} {button:?} pressed={pressed} {ago:?} ago");
+  ///   }
+  /// }
+  /// ```
+  pub fn last_changed(&self) -> Option<(Player, Button, bool, Duration)> {
+    let (player, button, pressed, at) = self.last_change?;
+    if self.hidden(player) {
+      return None;
+    }
+    Some((player, button, pressed, self.now - at))
+  }
+
+  /// Returns the state of both players' buttons and sticks on this frame,
+  /// as [`pressed`](DevcadeControls::pressed) and
+  /// [`stick`](DevcadeControls::stick) report them
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn record_system(input: DevcadeControls) {
+  ///   let snapshot = input.snapshot();
+  ///   println!("frame {}: {:?}", snapshot.frame, snapshot.pressed(Player::P1));
+  /// }
+  /// ```
+  pub fn snapshot(&self) -> InputSnapshot {
+    InputSnapshot::new(
+      self.frame,
+      self.now,
+      |player| self.pressed_buttons(player).collect(),
+      |player| self.stick(player),
+    )
+  }
+
+  /// Returns how long ago the player last began pressing the button, or
+  /// `None` if they haven't pressed it yet. Keeps counting after the button
+  /// is released. Also `None` while the player's input is hidden, like when
+  /// it's [paused](InputPaused) or [disabled](PlayerInputEnabled)
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  /// use std::time::Duration;
+  ///
+  /// fn combo_system(input: DevcadeControls) {
+  ///   let since_a1 = input.time_since_pressed(Player::P1, Button::A1);
+  ///   if input.just_pressed(Player::P1, Button::A2)
+  ///     && since_a1.is_some_and(|since| since < Duration::from_millis(300))
+  ///   {
+  ///     println!("Combo!");
+  ///   }
+  /// }
+  /// ```
+  pub fn time_since_pressed(&self, player: Player, button: Button) -> Option<Duration> {
+    if self.hidden(player) {
+      return None;
+    }
+    let button_state = self.get_player(player).get_state_for(button);
+    button_state.last_pressed_at.map(|at| self.now - at)
+  }
+
+  /// Returns the players who are holding a button or stick direction, or
+  /// began pressing one no more than `within` ago. Useful for only showing a
+  /// player's UI once they start playing.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  /// use std::time::Duration;
+  ///
+  /// fn player_ui_system(input: DevcadeControls) {
+  ///   for player in input.active_players(Duration::from_secs(30)) {
+  ///     println!("Showing {player:?}'s score");
+  ///   }
+  /// }
+  /// ```
+  pub fn active_players(&self, within: Duration) -> Vec<Player> {
+    enum_iterator::all::<Player>()
+      .filter(|&player| {
+        enum_iterator::all::<Button>().any(|button| {
+          self.pressed(player, button)
+            || self
+              .time_since_pressed(player, button)
+              .is_some_and(|since| since <= within)
+        })
+      })
+      .collect()
+  }
+
+  /// Returns true when nobody is holding anything and no button has been
+  /// pressed or released for more than `within`. Useful for dimming the
+  /// screen to save the cabinet's display
+  ///
+  /// Input is idle from the start, until the first press.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::DevcadeControls;
+  /// use std::time::Duration;
+  ///
+  /// fn dim_system(input: DevcadeControls) {
+  ///   if input.is_idle(Duration::from_secs(60)) {
+  ///     println!("Dimming the screen");
+  ///   }
+  /// }
+  /// ```
+  pub fn is_idle(&self, within: Duration) -> bool {
+    self.active_players(within).is_empty()
+      && self
+        .last_changed()
+        .is_none_or(|(_, _, _, ago)| ago > within)
+  }
+
+  /// Returns true when the button was just pressed for the second time, with
+  /// no more than `window` between the two presses
+  ///
+  /// Quick taps pair up, so the second and fourth taps of a quick run count
+  /// as double taps but the third doesn't. Runs of more than four taps may
+  /// pair up differently.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{Button, DevcadeControls, Player};
+  /// use std::time::Duration;
+  ///
+  /// fn dash_system(input: DevcadeControls) {
+  ///   let window = Duration::from_millis(200);
+  ///   if input.just_double_tapped(Player::P1, Button::StickRight, window) {
+  ///     println!("Dash!");
+  ///   }
+  /// }
+  /// ```
+  pub fn just_double_tapped(&self, player: Player, button: Button, window: Duration) -> bool {
+    if !self.just_pressed(player, button) {
+      return false;
+    }
+    let button_state = self.get_player(player).get_state_for(button);
+    let mut presses = std::iter::once(button_state.last_pressed_at)
+      .chain(button_state.earlier_presses)
+      .map_while(|press| press);
+    let Some(mut later) = presses.next() else {
+      return false;
+    };
+    // How many quick taps in a row end with this one
+    let mut run = 1;
+    for earlier in presses {
+      if later - earlier > window {
+        break;
+      }
+      run += 1;
+      later = earlier;
+    }
+    run % 2 == 0
+  }
+
+  /// Returns the name of the player's controller, or `None` if they don't
+  /// have one and are using the keyboard
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn show_controller(input: DevcadeControls) {
+  ///   let name = input.gamepad_name(Player::P1).unwrap_or("Keyboard");
+  ///   println!("P1 is using {name}");
+  /// }
+  /// ```
+  pub fn gamepad_name(&self, player: Player) -> Option<&str> {
+    self.get_player(player).gamepad_name.as_deref()
+  }
+
+  /// Returns every button the player is currently pressing
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn show_held_buttons(input: DevcadeControls) {
+  ///   for button in input.pressed_buttons(Player::P1) {
+  ///     println!("P1 is holding {button:?}");
+  ///   }
+  /// }
+  /// ```
+  pub fn pressed_buttons(&self, player: Player) -> impl Iterator<Item = Button> + '_ {
+    enum_iterator::all::<Button>().filter(move |button| self.pressed(player, *button))
+  }
+
+  /// Returns true if any player is pressing the button
+  pub fn pressed_by_any(&self, button: Button) -> bool {
+    enum_iterator::all::<Player>().any(|player| self.pressed(player, button))
+  }
+
+  /// Returns true if every player is pressing the button
+  pub fn pressed_by_all(&self, button: Button) -> bool {
+    enum_iterator::all::<Player>().all(|player| self.pressed(player, button))
+  }
+
+  /// Returns true if any player began pressing the button on this frame
+  pub fn just_pressed_by_any(&self, button: Button) -> bool {
+    enum_iterator::all::<Player>().any(|player| self.just_pressed(player, button))
+  }
+
+  /// Returns true on the frame either player presses [`Button::Menu`]. The
+  /// usual way to pause
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::DevcadeControls;
+  ///
+  /// #[derive(Resource, Default)]
+  /// struct Paused(bool);
+  ///
+  /// fn pause_system(input: DevcadeControls, mut paused: ResMut<Paused>) {
+  ///   if input.menu_pressed_any() {
+  ///     paused.0 = !paused.0;
+  ///   }
+  /// }
+  /// ```
+  pub fn menu_pressed_any(&self) -> bool {
+    self.just_pressed_by_any(Button::Menu)
+  }
+
+  /// Returns true while every player is holding [`Button::Menu`]. The usual
+  /// way to quit, used by [`exit_on_menu_buttons`]
+  pub fn menu_held_by_all(&self) -> bool {
+    self.pressed_by_all(Button::Menu)
+  }
+
+  /// Returns every button the player began pressing on this frame
+  pub fn just_pressed_buttons(&self, player: Player) -> impl Iterator<Item = Button> + '_ {
+    enum_iterator::all::<Button>().filter(move |button| self.just_pressed(player, *button))
+  }
+
+  /// Returns true if the player is pressing any button in
+  /// [the top row](Button::A_ROW). Handy for menus where any of them confirms
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::{DevcadeControls, Player};
+  ///
+  /// fn menu_system(input: DevcadeControls) {
+  ///   if input.any_a_just_pressed(Player::P1) {
+  ///     println!("Confirm");
+  ///   } else if input.any_b_just_pressed(Player::P1) {
+  ///     println!("Back");
+  ///   }
+  /// }
+  /// ```
+  pub fn any_a_pressed(&self, player: Player) -> bool {
+    Button::A_ROW
+      .into_iter()
+      .any(|button| self.pressed(player, button))
+  }
+
+  /// Returns true if the player is pressing any button in
+  /// [the second row](Button::B_ROW). Handy for menus where any of them
+  /// cancels
+  pub fn any_b_pressed(&self, player: Player) -> bool {
+    Button::B_ROW
+      .into_iter()
+      .any(|button| self.pressed(player, button))
+  }
+
+  /// Returns true if the player began pressing any button in
+  /// [the top row](Button::A_ROW) on this frame
+  pub fn any_a_just_pressed(&self, player: Player) -> bool {
+    Button::A_ROW
+      .into_iter()
+      .any(|button| self.just_pressed(player, button))
+  }
+
+  /// Returns true if the player began pressing any button in
+  /// [the second row](Button::B_ROW) on this frame
+  pub fn any_b_just_pressed(&self, player: Player) -> bool {
+    Button::B_ROW
+      .into_iter()
+      .any(|button| self.just_pressed(player, button))
   }
 }
 
-#[derive(Debug, Clone, Copy, Sequence, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Sequence, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 /// Gamepad buttons
+///
+/// Buttons are ordered the way they're declared here, which is also the
+/// order of their [indices](Button::index).
 pub enum Button {
   /// Top row, first button. Red
   A1,
@@ -202,7 +1277,7 @@ pub enum Button {
   B2,
   /// Second row, third button.
   B3,
-  /// Second row, third button.
+  /// Second row, fourth button.
   B4,
 
   /// Center button. Black. Generally bound to pause or exit
@@ -218,6 +1293,143 @@ pub enum Button {
   StickRight,
 }
 
+impl Button {
+  /// How many buttons there are
+  pub const COUNT: usize = <Button as Sequence>::CARDINALITY;
+
+  /// The top row of buttons, A1 to A4
+  pub const A_ROW: [Button; 4] = [Button::A1, Button::A2, Button::A3, Button::A4];
+
+  /// The second row of buttons, B1 to B4
+  pub const B_ROW: [Button; 4] = [Button::B1, Button::B2, Button::B3, Button::B4];
+
+  /// Returns every button, in [index](Button::index) order
+  pub fn all() -> impl Iterator<Item = Button> {
+    enum_iterator::all::<Button>()
+  }
+
+  /// Returns the button's position in the declaration order, from 0 to
+  /// [`Button::COUNT`]` - 1`. Handy for storing something per button in an
+  /// array.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::Button;
+  ///
+  /// assert_eq!(Button::A1.index(), 0);
+  /// assert_eq!(Button::StickRight.index(), Button::COUNT - 1);
+  /// for (index, button) in Button::all().enumerate() {
+  ///   assert_eq!(button.index(), index);
+  ///   assert_eq!(Button::from_index(index), Some(button));
+  /// }
+  /// assert_eq!(Button::from_index(Button::COUNT), None);
+  /// ```
+  pub fn index(&self) -> usize {
+    *self as usize
+  }
+
+  /// Returns the button at `index` in the declaration order, the inverse of
+  /// [`Button::index`]
+  pub fn from_index(index: usize) -> Option<Button> {
+    Button::all().nth(index)
+  }
+
+  /// Describes the physical control on the cabinet, for on-screen prompts
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::Button;
+  ///
+  /// assert_eq!(Button::A1.cabinet_label(), "Red button");
+  /// assert_eq!(Button::Menu.cabinet_label(), "Black center button");
+  /// assert_eq!(Button::StickLeft.cabinet_label(), "Joystick left");
+  /// assert!(Button::all().all(|button| !button.cabinet_label().is_empty()));
+  /// ```
+  pub fn cabinet_label(&self) -> &'static str {
+    match self {
+      Button::A1 => "Red button",
+      Button::A2 => "Blue button",
+      Button::A3 => "Green button",
+      Button::A4 => "White button",
+      Button::B1 => "Second row, first button",
+      Button::B2 => "Second row, second button",
+      Button::B3 => "Second row, third button",
+      Button::B4 => "Second row, fourth button",
+      Button::Menu => "Black center button",
+      Button::StickLeft => "Joystick left",
+      Button::StickUp => "Joystick up",
+      Button::StickDown => "Joystick down",
+      Button::StickRight => "Joystick right",
+    }
+  }
+
+  /// Returns the button on the opposite side of the controls, for
+  /// [`DevcadeInputConfig::mirror_layout`]
+  ///
+  /// Each row of buttons is flipped, so [`Button::A1`] swaps with
+  /// [`Button::A4`] and [`Button::A2`] with [`Button::A3`], and likewise for
+  /// the B row. [`Button::StickLeft`] and [`Button::StickRight`] swap too.
+  /// [`Button::Menu`] and the vertical stick directions stay put.
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::Button;
+  ///
+  /// assert_eq!(Button::B2.mirrored(), Button::B3);
+  /// assert_eq!(Button::StickUp.mirrored(), Button::StickUp);
+  /// for button in enum_iterator::all::<Button>() {
+  ///   assert_eq!(button.mirrored().mirrored(), button);
+  /// }
+  /// ```
+  pub fn mirrored(&self) -> Button {
+    match self {
+      Button::A1 => Button::A4,
+      Button::A2 => Button::A3,
+      Button::A3 => Button::A2,
+      Button::A4 => Button::A1,
+      Button::B1 => Button::B4,
+      Button::B2 => Button::B3,
+      Button::B3 => Button::B2,
+      Button::B4 => Button::B1,
+      Button::StickLeft => Button::StickRight,
+      Button::StickRight => Button::StickLeft,
+      Button::Menu | Button::StickUp | Button::StickDown => *self,
+    }
+  }
+
+  /// Returns the controller button this button is, or for the stick
+  /// directions, the matching D-pad button.
+  ///
+  /// Unlike converting with [`GamepadButtonType::try_from`], this works for
+  /// every button, which is handy for looking up button glyphs. The stick
+  /// directions are still read from the stick, not the D-pad.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::Button;
+  /// use std::collections::HashSet;
+  ///
+  /// assert_eq!(Button::A1.gamepad_button_type(), GamepadButtonType::West);
+  /// assert_eq!(Button::StickUp.gamepad_button_type(), GamepadButtonType::DPadUp);
+  ///
+  /// // Every button maps to a different controller button
+  /// let types: HashSet<_> = enum_iterator::all::<Button>()
+  ///   .map(|button| button.gamepad_button_type())
+  ///   .collect();
+  /// assert_eq!(types.len(), 13);
+  /// ```
+  pub fn gamepad_button_type(&self) -> GamepadButtonType {
+    match self {
+      Button::StickLeft => GamepadButtonType::DPadLeft,
+      Button::StickUp => GamepadButtonType::DPadUp,
+      Button::StickDown => GamepadButtonType::DPadDown,
+      Button::StickRight => GamepadButtonType::DPadRight,
+      button => GamepadButtonType::try_from(button).expect("Only stick buttons are axes"),
+    }
+  }
+}
+
 impl TryFrom<&Button> for GamepadButtonType {
   type Error = ();
   fn try_from(value: &Button) -> Result<Self, Self::Error> {
@@ -236,6 +1448,152 @@ impl TryFrom<&Button> for GamepadButtonType {
   }
 }
 
+#[derive(Debug, Clone, Copy, Sequence, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+/// One of the 8 directions a stick can point, as returned by
+/// [`DevcadeControls::stick_direction`]
+pub enum Direction8 {
+  /// Up
+  N,
+  /// Up and right
+  NE,
+  /// Right
+  E,
+  /// Down and right
+  SE,
+  /// Down
+  S,
+  /// Down and left
+  SW,
+  /// Left
+  W,
+  /// Up and left
+  NW,
+}
+
+impl Direction8 {
+  /// Returns the direction closest to the angle of `stick`, or `None` if
+  /// it's centered. Each direction covers the 45 degrees around it.
+  ///
+  /// # Examples
+  /// ```
+  /// use bevy::prelude::*;
+  /// use devcaders::Direction8;
+  ///
+  /// assert_eq!(Direction8::from_stick(Vec2::ZERO), None);
+  /// // Either side of every boundary between two directions
+  /// for (index, direction) in enum_iterator::all::<Direction8>().enumerate() {
+  ///   let next = enum_iterator::next_cycle(&direction).unwrap();
+  ///   // Clockwise from straight up
+  ///   let boundary = (index as f32 * 45.0 + 22.5).to_radians();
+  ///   for (offset, expected) in [(-0.5, direction), (0.5, next)] {
+  ///     let angle = boundary + f32::to_radians(offset);
+  ///     let stick = Vec2::new(angle.sin(), angle.cos());
+  ///     assert_eq!(Direction8::from_stick(stick), Some(expected));
+  ///     assert_eq!(Direction8::from_stick(stick * 0.1), Some(expected));
+  ///   }
+  /// }
+  /// ```
+  pub fn from_stick(stick: Vec2) -> Option<Self> {
+    if stick == Vec2::ZERO {
+      return None;
+    }
+    // Eighths of a turn clockwise from straight up
+    let octant = (stick.x.atan2(stick.y) / std::f32::consts::FRAC_PI_4).round() as i32;
+    Some(match octant.rem_euclid(8) {
+      0 => Direction8::N,
+      1 => Direction8::NE,
+      2 => Direction8::E,
+      3 => Direction8::SE,
+      4 => Direction8::S,
+      5 => Direction8::SW,
+      6 => Direction8::W,
+      _ => Direction8::NW,
+    })
+  }
+
+  /// Returns an arrow pointing this way, for input displays
+  ///
+  /// # Examples
+  /// ```
+  /// use devcaders::Direction8;
+  ///
+  /// assert_eq!(Direction8::N.glyph(), '↑');
+  /// assert_eq!(Direction8::SE.glyph(), '↘');
+  /// ```
+  pub fn glyph(&self) -> char {
+    match self {
+      Direction8::N => '↑',
+      Direction8::NE => '↗',
+      Direction8::E => '→',
+      Direction8::SE => '↘',
+      Direction8::S => '↓',
+      Direction8::SW => '↙',
+      Direction8::W => '←',
+      Direction8::NW => '↖',
+    }
+  }
+}
+
+/// The direction each player's stick last pointed, for characters that keep
+/// facing the same way when the stick is let go
+///
+/// [`DevcadePlugin`] adds this and keeps it up to date from
+/// [`DevcadeControls::stick_direction`] in [`DevcadeInputSet`]. It only
+/// changes while the stick is outside the deadzone.
+///
+/// # Examples
+/// ```
+/// # use bevy::prelude::*;
+/// # use devcaders::{Direction8, Player, StickFacing};
+/// fn pick_sprite(facing: Res<StickFacing>) {
+///   let sprite = match facing.facing(Player::P1) {
+///     Direction8::W | Direction8::NW | Direction8::SW => "walk_left.png",
+///     _ => "walk_right.png",
+///   };
+///   println!("Drawing {sprite}");
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StickFacing {
+  p1: Direction8,
+  p2: Direction8,
+}
+
+impl Default for StickFacing {
+  fn default() -> Self {
+    Self {
+      p1: Direction8::S,
+      p2: Direction8::S,
+    }
+  }
+}
+
+impl StickFacing {
+  /// Returns the direction the player's stick last pointed, or
+  /// [`Direction8::S`] if it hasn't been moved yet
+  pub fn facing(&self, player: Player) -> Direction8 {
+    match player {
+      Player::P1 => self.p1,
+      Player::P2 => self.p2,
+    }
+  }
+}
+
+fn update_stick_facing(input: DevcadeControls, mut facing: ResMut<StickFacing>) {
+  for player in enum_iterator::all::<Player>() {
+    let Some(direction) = input.stick_direction(player) else {
+      continue;
+    };
+    if facing.facing(player) != direction {
+      match player {
+        Player::P1 => facing.p1 = direction,
+        Player::P2 => facing.p2 = direction,
+      }
+    }
+  }
+}
+
 enum AxisConfig {
   Positive(GamepadAxisType),
   Negative(GamepadAxisType),
@@ -248,6 +1606,18 @@ impl AxisConfig {
       AxisConfig::Negative(axis_type) => axis_type,
     }
   }
+
+  /// Whether `stick` is pushed in this direction
+  fn pressed(&self, stick: Vec2) -> bool {
+    let value = match self.get_axis() {
+      GamepadAxisType::LeftStickX => stick.x,
+      _ => stick.y,
+    };
+    match self {
+      AxisConfig::Positive(_) => value > 0.0,
+      AxisConfig::Negative(_) => value < 0.0,
+    }
+  }
 }
 
 impl TryFrom<&Button> for AxisConfig {
@@ -264,6 +1634,9 @@ impl TryFrom<&Button> for AxisConfig {
 }
 
 /// Internal. Tuple of [`Player`] and [`Button`]
+///
+/// Converting into a [`KeyCode`] gives the default keyboard binding used by
+/// [`KeyboardBindings`]
 pub struct PlayerButton {
   player: Player,
   button: Button,
@@ -303,7 +1676,8 @@ impl From<PlayerButton> for KeyCode {
   }
 }
 
-#[derive(Debug, Clone, Copy, Sequence, PartialEq, Eq, Component)]
+#[derive(Debug, Clone, Copy, Sequence, PartialEq, Eq, Hash, Component)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 /// Used to specify which player's controls to query
 pub enum Player {
   /// First player, left set of controls
@@ -322,137 +1696,1080 @@ impl Player {
 }
 
 impl<'w> DevcadeControlsInner<'w> {
-  fn gamepad_for_player(&self, player: &Player) -> Option<Gamepad> {
-    self.gamepads.iter().nth(player.index())
-  }
-  /// Returns true if the button is pressed by the given player
-  /// Uses keyboard if no controller is plugged in.
-  /// See source for [`PlayerButton`] for more detailed mappings
-  pub fn pressed(&self, button: Button, player: Player) -> bool {
-    if let Some(gamepad) = self.gamepad_for_player(&player) {
-      if let Ok(button) = GamepadButtonType::try_from(&button) {
-        self
-          .button_inputs
-          .pressed(GamepadButton::new(gamepad, button))
-      } else {
-        let axis_config = AxisConfig::try_from(&button).unwrap();
-        let value = self
-          .axes
-          .get(GamepadAxis::new(gamepad, axis_config.get_axis()))
-          .unwrap();
-        match axis_config {
-          AxisConfig::Positive(_) => value > 0.0,
-          AxisConfig::Negative(_) => value < 0.0,
-        }
-      }
-    } else {
-      self
+  /// Builds a resolver, reading from `no_input` for any of bevy's input
+  /// resources that don't exist
+  fn resolver<'a>(&'a self, no_input: &'a NoInput) -> ButtonResolver<'a> {
+    ButtonResolver {
+      gamepads: self.gamepads.as_deref().unwrap_or(&no_input.gamepads),
+      button_inputs: self
+        .button_inputs
+        .as_deref()
+        .unwrap_or(&no_input.button_inputs),
+      button_axes: self.button_axes.as_deref().unwrap_or(&no_input.button_axes),
+      axes: self.axes.as_deref().unwrap_or(&no_input.axes),
+      keyboard_input: self
         .keyboard_input
-        .pressed(KeyCode::from(PlayerButton { button, player }))
+        .as_deref()
+        .unwrap_or(&no_input.keyboard_input),
+      keyboard_bindings: &self.keyboard_bindings,
+      input_override: &self.input_override,
+      config: &self.config,
     }
   }
 }
 
-/// Close the focused window when both menu buttons are pressed.
-pub fn close_on_menu_buttons(
-  mut commands: Commands,
-  focused_windows: Query<(Entity, &Window)>,
-  input: DevcadeControls,
-) {
-  for (window, focus) in focused_windows.iter() {
-    if !focus.focused {
-      continue;
-    }
-    if input.pressed(Player::P1, Button::Menu) && input.pressed(Player::P2, Button::Menu) {
-      commands.entity(window).despawn();
-    }
-  }
-}
+/// Plugin that sets up the resources and systems used by devcaders
+///
+/// [`DevcadeControls`] works without it, but features like [`InputOverride`]
+/// need it to run their per-frame bookkeeping.
+pub struct DevcadePlugin;
 
-struct CellWrapper<T>(OnceLock<T>);
-impl<T> CellWrapper<T> {
-  const fn new() -> Self {
-    Self(OnceLock::new())
+impl Plugin for DevcadePlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<KeyboardBindings>()
+      .init_resource::<InputOverride>()
+      .init_resource::<DevcadeInputConfig>()
+      .init_resource::<InputCaptureMode>()
+      .init_resource::<InputPaused>()
+      .init_resource::<PlayerInputEnabled>()
+      .init_resource::<InputReset>()
+      .init_resource::<StickFacing>()
+      .init_resource::<HoldToExit>()
+      .init_resource::<MenuCloseConfig>()
+      .init_resource::<ExitChord>()
+      .init_resource::<StuckButtonConfig>()
+      .init_resource::<PlayerConnectionCallbacks>()
+      .add_event::<DevcadeNoInputAvailable>()
+      .add_event::<DevcadeStuckButton>()
+      .add_event::<DevcadePlayerConnection>()
+      .configure_sets(PreUpdate, DevcadeInputSet.after(InputSystem))
+      .add_systems(PreUpdate, config::validate_input_config)
+      .add_systems(
+        PreUpdate,
+        (
+          input_override::tick_input_overrides,
+          detect_no_input,
+          detect_stuck_buttons.after(input_override::tick_input_overrides),
+          update_stick_facing.after(input_override::tick_input_overrides),
+          detect_player_connections,
+        )
+          .in_set(DevcadeInputSet)
+          .after(config::validate_input_config),
+      );
+    #[cfg(all(feature = "backend", not(target_os = "windows")))]
+    app
+      .init_resource::<NfcReaderMapping>()
+      .init_resource::<BackendClientResource>()
+      .init_resource::<NfcPoller>()
+      .add_systems(PreUpdate, nfc_poller::poll_nfc);
   }
 }
 
-#[cfg(not(target_os = "windows"))]
-impl Deref for CellWrapper<BackendClient> {
-  type Target = BackendClient;
-  fn deref(&self) -> &Self::Target {
-    self.0.get_or_init(Self::Target::default)
+/// The systems [`DevcadePlugin`] uses to update input each frame
+///
+/// They run in [`PreUpdate`], after bevy's own
+/// [`InputSystem`](bevy::input::InputSystem). Systems in [`Update`] and later
+/// always see the updated state. Systems in [`PreUpdate`] that read
+/// [`DevcadeControls`], [`InputOverride`] or [`DevcadeNoInputAvailable`]
+/// should be ordered `.after(DevcadeInputSet)`.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{DevcadeControls, DevcadeInputSet, DevcadePlugin};
+///
+/// fn early_system(input: DevcadeControls) {
+///   // Sees this frame's input
+/// }
+///
+/// App::new()
+///   .add_plugins(DevcadePlugin)
+///   .add_systems(PreUpdate, early_system.after(DevcadeInputSet));
+/// ```
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DevcadeInputSet;
+
+/// Sent by [`DevcadePlugin`] on every frame where nobody can play, because no
+/// controllers are connected and [`DevcadeInputConfig::keyboard_fallback`]
+/// is off
+///
+/// Show a "connect a controller" screen for as long as these keep coming.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::DevcadeNoInputAvailable;
+///
+/// #[derive(Resource, Default)]
+/// struct ShowConnectScreen(bool);
+///
+/// fn connect_screen_system(
+///   mut no_input: EventReader<DevcadeNoInputAvailable>,
+///   mut show: ResMut<ShowConnectScreen>,
+/// ) {
+///   show.0 = no_input.read().count() > 0;
+/// }
+/// ```
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevcadeNoInputAvailable;
+
+fn detect_no_input(
+  gamepads: Option<Res<Gamepads>>,
+  config: Res<DevcadeInputConfig>,
+  mut no_input: EventWriter<DevcadeNoInputAvailable>,
+) {
+  let no_gamepads = gamepads.is_none_or(|gamepads| gamepads.iter().next().is_none());
+  if !config.keyboard_fallback && no_gamepads {
+    no_input.send(DevcadeNoInputAvailable);
   }
 }
 
-#[cfg(not(target_os = "windows"))]
-static CLIENT: CellWrapper<BackendClient> = CellWrapper::new();
-
-/// Represents an inflight request to the backend for NFC tags on the reader
-/// You can spawn an entity with this component to poll the request:
+/// Sent by [`DevcadePlugin`] when a button has been held for longer than
+/// [`StuckButtonConfig::threshold`], which usually means it's physically
+/// stuck
 ///
-/// # Example
+/// It's sent once when the threshold passes, and not again until the button
+/// has been released and gets stuck again. A warning is logged alongside it,
+/// so operators can find faulty hardware even if the game ignores it.
+///
+/// # Examples
 /// ```
-/// #[derive(Component, Deref, DerefMut)]
-/// struct MyNfcTagRequest(NfcTagRequestComponent);
-/// fn nfc_system(mut commands: Commands, mut tags_request: Query<(&mut MyNfcTagRequest, Entity)>) {
-///   for (mut tags_request, id) in &mut tags_request {
-///     if let Some(tag) = tags_request.poll() {
-///       println!("Got a response! {tag:?}");
-///       commands.entity(id).despawn();
-///     }
-///   }
-///   if tags_request.is_empty() {
-///     println!("Creating a new request...");
-///     commands.spawn(MyNfcTagRequest(NfcTagRequestComponent::new()));
+/// use bevy::prelude::*;
+/// use devcaders::DevcadeStuckButton;
+///
+/// fn stuck_button_system(mut stuck: EventReader<DevcadeStuckButton>) {
+///   for stuck in stuck.read() {
+///     println!("{:?}'s {:?} looks stuck", stuck.player, stuck.button);
 ///   }
 /// }
 /// ```
-#[derive(Component)]
-#[cfg(not(target_os = "windows"))]
-pub struct NfcTagRequestComponent(Task<Result<Option<String>, RequestError>>);
-#[cfg(not(target_os = "windows"))]
-impl Default for NfcTagRequestComponent {
-  fn default() -> Self {
-    Self::new()
-  }
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevcadeStuckButton {
+  /// Player whose button is stuck
+  pub player: Player,
+  /// The stuck button
+  pub button: Button,
 }
 
-#[cfg(not(target_os = "windows"))]
-impl NfcTagRequestComponent {
-  /// Creates a new `NfcTagRequestComponent`
-  pub fn new() -> Self {
+/// Settings for [`DevcadeStuckButton`]
+///
+/// [`DevcadePlugin`] adds the default one.
+#[derive(Resource, Debug, Clone)]
+pub struct StuckButtonConfig {
+  /// How long a button has to be held before it's reported as stuck.
+  /// Defaults to 30 seconds
+  pub threshold: Duration,
+}
+
+impl Default for StuckButtonConfig {
+  fn default() -> Self {
+    Self {
+      threshold: Duration::from_secs(30),
+    }
+  }
+}
+
+fn detect_stuck_buttons(
+  input: DevcadeControls,
+  config: Res<StuckButtonConfig>,
+  mut reported: Local<HashSet<(Player, Button)>>,
+  mut stuck: EventWriter<DevcadeStuckButton>,
+) {
+  for player in enum_iterator::all::<Player>() {
+    for button in Button::all() {
+      let held_for = input
+        .pressed(player, button)
+        .then(|| input.time_since_pressed(player, button))
+        .flatten();
+      let Some(held_for) = held_for else {
+        reported.remove(&(player, button));
+        continue;
+      };
+      if held_for >= config.threshold && reported.insert((player, button)) {
+        log::warn!("{player:?}'s {button:?} has been held for {held_for:?}, it may be stuck");
+        stuck.send(DevcadeStuckButton { player, button });
+      }
+    }
+  }
+}
+
+/// Sent by [`DevcadePlugin`] when a player gets their first controller or
+/// loses their last one. See [`PlayerConnectionCallbacks`] for reacting to
+/// it without an event reader
+///
+/// Players using the keyboard fallback aren't considered connected.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevcadePlayerConnection {
+  /// The player
+  pub player: Player,
+  /// Whether they're connected now
+  pub connected: bool,
+}
+
+/// Callback registered with [`PlayerConnectionCallbacks`]
+type PlayerConnectionCallback = Box<dyn FnMut(&mut Commands, Player) + Send + Sync>;
+
+/// Closures [`DevcadePlugin`] calls when a player connects or disconnects,
+/// for games that would rather not read [`DevcadePlayerConnection`] events
+///
+/// Callbacks run in [`DevcadeInputSet`], and are given [`Commands`] for
+/// spawning or despawning the player's UI.
+///
+/// # Examples
+/// ```
+/// use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadInfo};
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{DevcadePlugin, Player, PlayerConnectionCallbacks};
+///
+/// #[derive(Component)]
+/// struct PlayerUi(Player);
+///
+/// let mut app = App::new();
+/// app.add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin));
+/// let mut callbacks = app.world.resource_mut::<PlayerConnectionCallbacks>();
+/// callbacks.on_player_connected(|commands, player| {
+///   commands.spawn(PlayerUi(player));
+/// });
+/// callbacks.on_player_disconnected(|commands, player| {
+///   commands.add(move |world: &mut World| {
+///     let ui = world
+///       .query::<(Entity, &PlayerUi)>()
+///       .iter(world)
+///       .find(|(_, ui)| ui.0 == player)
+///       .map(|(entity, _)| entity);
+///     if let Some(ui) = ui {
+///       world.despawn(ui);
+///     }
+///   });
+/// });
+///
+/// let connection = |id, connected| {
+///   let connection = if connected {
+///     GamepadConnection::Connected(GamepadInfo {
+///       name: format!("Pad {id}"),
+///     })
+///   } else {
+///     GamepadConnection::Disconnected
+///   };
+///   GamepadConnectionEvent::new(Gamepad::new(id), connection)
+/// };
+/// let uis = |app: &mut App| {
+///   let mut players: Vec<_> = app
+///     .world
+///     .query::<&PlayerUi>()
+///     .iter(&app.world)
+///     .map(|ui| ui.0)
+///     .collect();
+///   players.sort_by_key(|&player| player == Player::P2);
+///   players
+/// };
+///
+/// app.update();
+/// assert_eq!(uis(&mut app), []);
+///
+/// app.world.send_event(connection(0, true));
+/// app.update();
+/// assert_eq!(uis(&mut app), [Player::P1]);
+///
+/// app.world.send_event(connection(1, true));
+/// app.update();
+/// assert_eq!(uis(&mut app), [Player::P1, Player::P2]);
+///
+/// app.world.send_event(connection(1, false));
+/// app.update();
+/// assert_eq!(uis(&mut app), [Player::P1]);
+/// ```
+#[derive(Resource, Default)]
+pub struct PlayerConnectionCallbacks {
+  connected: Vec<PlayerConnectionCallback>,
+  disconnected: Vec<PlayerConnectionCallback>,
+}
+
+impl PlayerConnectionCallbacks {
+  /// Calls `callback` whenever a player gets their first controller
+  pub fn on_player_connected(
+    &mut self,
+    callback: impl FnMut(&mut Commands, Player) + Send + Sync + 'static,
+  ) {
+    self.connected.push(Box::new(callback));
+  }
+
+  /// Calls `callback` whenever a player loses their last controller
+  pub fn on_player_disconnected(
+    &mut self,
+    callback: impl FnMut(&mut Commands, Player) + Send + Sync + 'static,
+  ) {
+    self.disconnected.push(Box::new(callback));
+  }
+}
+
+fn detect_player_connections(
+  mut commands: Commands,
+  input: DevcadeControls,
+  mut connected: Local<HashSet<Player>>,
+  mut callbacks: ResMut<PlayerConnectionCallbacks>,
+  mut connections: EventWriter<DevcadePlayerConnection>,
+) {
+  for player in enum_iterator::all::<Player>() {
+    let is_connected = input
+      .gamepad_assignments()
+      .iter()
+      .any(|assignment| assignment.player == Some(player));
+    if is_connected == connected.contains(&player) {
+      continue;
+    }
+    let callbacks = if is_connected {
+      connected.insert(player);
+      &mut callbacks.connected
+    } else {
+      connected.remove(&player);
+      &mut callbacks.disconnected
+    };
+    for callback in callbacks {
+      callback(&mut commands, player);
+    }
+    connections.send(DevcadePlayerConnection {
+      player,
+      connected: is_connected,
+    });
+  }
+}
+
+/// Settings for [`close_on_menu_buttons`] and [`exit_on_menu_buttons`]
+///
+/// [`DevcadePlugin`] adds the default one. Without this resource, the
+/// systems behave as if it were the default.
+///
+/// # Examples
+/// A button held down while the game starts doesn't close it straight away:
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+/// use devcaders::{close_on_menu_buttons, DevcadePrimaryWindow, MenuCloseConfig};
+/// use std::time::Duration;
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+///   .insert_resource(MenuCloseConfig {
+///     startup_grace: Duration::from_secs(1),
+///   })
+///   .add_systems(Update, close_on_menu_buttons);
+/// let window = app.world.spawn((Window::default(), DevcadePrimaryWindow)).id();
+///
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+/// for _ in 0..4 {
+///   app.update();
+///   assert!(app.world.get_entity(window).is_some());
+/// }
+/// app.update();
+/// assert!(app.world.get_entity(window).is_none());
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MenuCloseConfig {
+  /// How long after the system first runs to ignore the menu buttons, so
+  /// a button that's stuck or held while the cabinet boots doesn't close the
+  /// game as soon as it opens. Defaults to zero
+  pub startup_grace: Duration,
+}
+
+impl MenuCloseConfig {
+  /// Returns true while the menu buttons should be ignored. `started` is when
+  /// the system first ran, and is set if it hasn't been
+  fn in_startup_grace(
+    config: Option<&Self>,
+    started: &mut Option<Duration>,
+    now: Duration,
+  ) -> bool {
+    let started = *started.get_or_insert(now);
+    config.is_some_and(|config| now - started < config.startup_grace)
+  }
+}
+
+/// Marks the window [`close_on_menu_buttons`] should close
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct DevcadePrimaryWindow;
+
+/// Close the game's window when both menu buttons are pressed.
+///
+/// Closes the windows marked with [`DevcadePrimaryWindow`], or the focused
+/// window if none are marked. See [`MenuCloseConfig`] for ignoring the
+/// buttons just after startup.
+///
+/// # Examples
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{close_on_menu_buttons, DevcadePrimaryWindow};
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .add_systems(Update, close_on_menu_buttons);
+/// let focused = app.world.spawn(Window::default()).id();
+/// let game = app
+///   .world
+///   .spawn((
+///     Window {
+///       focused: false,
+///       ..default()
+///     },
+///     DevcadePrimaryWindow,
+///   ))
+///   .id();
+///
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+/// app.update();
+/// assert!(app.world.get_entity(game).is_none());
+/// assert!(app.world.get_entity(focused).is_some());
+/// ```
+pub fn close_on_menu_buttons(
+  mut commands: Commands,
+  windows: Query<(Entity, &Window, Has<DevcadePrimaryWindow>)>,
+  input: DevcadeControls,
+  config: Option<Res<MenuCloseConfig>>,
+  mut started: Local<Option<Duration>>,
+) {
+  if MenuCloseConfig::in_startup_grace(config.as_deref(), &mut started, input.now)
+    || !input.menu_held_by_all()
+  {
+    return;
+  }
+  let any_marked = windows.iter().any(|(_, _, marked)| marked);
+  for (window, focus, marked) in windows.iter() {
+    let target = if any_marked { marked } else { focus.focused };
+    if target {
+      commands.entity(window).despawn();
+    }
+  }
+}
+
+/// Exit the app when both menu buttons are pressed.
+///
+/// Unlike [`close_on_menu_buttons`], this sends [`AppExit`]
+/// so the app shuts down normally no matter how many windows it has. Also
+/// honors [`MenuCloseConfig`].
+///
+/// # Examples
+/// ```
+/// use bevy::{app::AppExit, input::InputPlugin, prelude::*};
+/// use devcaders::exit_on_menu_buttons;
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .add_systems(Update, exit_on_menu_buttons);
+///
+/// app.update();
+/// assert!(app.world.resource::<Events<AppExit>>().is_empty());
+///
+/// // Both players' menu buttons are bound to escape on the keyboard
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+/// app.update();
+/// assert!(!app.world.resource::<Events<AppExit>>().is_empty());
+/// ```
+pub fn exit_on_menu_buttons(
+  input: DevcadeControls,
+  config: Option<Res<MenuCloseConfig>>,
+  mut started: Local<Option<Duration>>,
+  mut exit: EventWriter<AppExit>,
+) {
+  if !MenuCloseConfig::in_startup_grace(config.as_deref(), &mut started, input.now)
+    && input.menu_held_by_all()
+  {
+    exit.send(AppExit);
+  }
+}
+
+/// Settings and progress for [`exit_on_menu_hold`]
+///
+/// Insert your own copy of this resource to change the button or how long it
+/// has to be held. [`DevcadePlugin`] adds the default one.
+#[derive(Resource, Debug, Clone)]
+pub struct HoldToExit {
+  /// The button either player holds to quit. Defaults to [`Button::Menu`]
+  pub button: Button,
+  /// How long the button has to be held. Defaults to two seconds
+  pub duration: Duration,
+  progress: f32,
+}
+
+impl HoldToExit {
+  /// Creates settings that quit once `button` is held for `duration`
+  pub fn new(button: Button, duration: Duration) -> Self {
+    Self {
+      button,
+      duration,
+      progress: 0.0,
+    }
+  }
+
+  /// How far along the hold is, from 0 when the button isn't held to 1 when
+  /// the app is exiting. Handy for drawing a filling ring
+  pub fn progress(&self) -> f32 {
+    self.progress
+  }
+}
+
+impl Default for HoldToExit {
+  fn default() -> Self {
+    Self::new(Button::Menu, Duration::from_secs(2))
+  }
+}
+
+/// Exit the app when either player holds a button, [`Button::Menu`] by
+/// default, for a while.
+///
+/// An alternative to [`exit_on_menu_buttons`] for cabinets with a single
+/// player, where pressing both menu buttons isn't possible. Configured with
+/// the [`HoldToExit`] resource, which also reports how far along the hold is.
+///
+/// # Examples
+/// ```
+/// use bevy::{app::AppExit, input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+/// use devcaders::{exit_on_menu_hold, Button, HoldToExit};
+/// use std::time::Duration;
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)))
+///   .insert_resource(HoldToExit::new(Button::Menu, Duration::from_secs(1)))
+///   .add_systems(Update, exit_on_menu_hold);
+///
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+/// for _ in 0..4 {
+///   app.update();
+///   assert!(app.world.resource::<Events<AppExit>>().is_empty());
+/// }
+/// assert_eq!(app.world.resource::<HoldToExit>().progress(), 0.75);
+///
+/// // Letting go starts over
+/// app.world.resource_mut::<Input<KeyCode>>().release(KeyCode::Escape);
+/// app.update();
+/// assert_eq!(app.world.resource::<HoldToExit>().progress(), 0.0);
+///
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+/// for _ in 0..4 {
+///   app.update();
+///   assert!(app.world.resource::<Events<AppExit>>().is_empty());
+/// }
+/// app.update();
+/// assert_eq!(app.world.resource::<HoldToExit>().progress(), 1.0);
+/// assert!(!app.world.resource::<Events<AppExit>>().is_empty());
+/// ```
+pub fn exit_on_menu_hold(
+  input: DevcadeControls,
+  mut hold: ResMut<HoldToExit>,
+  mut exit: EventWriter<AppExit>,
+) {
+  let held_for = enum_iterator::all::<Player>()
+    .filter(|&player| input.pressed(player, hold.button))
+    .filter_map(|player| input.time_since_pressed(player, hold.button))
+    .max();
+  let progress = match held_for {
+    Some(_) if hold.duration.is_zero() => 1.0,
+    Some(held_for) => (held_for.as_secs_f32() / hold.duration.as_secs_f32()).min(1.0),
+    None => 0.0,
+  };
+  if hold.progress != progress {
+    hold.progress = progress;
+  }
+  if held_for.is_some_and(|held_for| held_for >= hold.duration) {
+    exit.send(AppExit);
+  }
+}
+
+/// The button [`exit_on_dev_quit_button`] quits on
+///
+/// A development convenience for quitting with one button instead of
+/// both menu buttons. Nothing adds this resource, so insert it only in builds
+/// that won't run on the cabinet, where one stray press would end someone's
+/// game:
+/// ```
+/// # use bevy::prelude::*;
+/// # use devcaders::{exit_on_dev_quit_button, Button, DevQuitButton, Player};
+/// # let mut app = App::new();
+/// #[cfg(debug_assertions)]
+/// app
+///   .insert_resource(DevQuitButton::new(Player::P1, Button::B4))
+///   .add_systems(Update, exit_on_dev_quit_button);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevQuitButton {
+  /// Player whose button it is
+  pub player: Player,
+  /// The button
+  pub button: Button,
+}
+
+impl DevQuitButton {
+  /// Quits when `player` presses `button`
+  pub fn new(player: Player, button: Button) -> Self {
+    Self { player, button }
+  }
+}
+
+/// Exit the app as soon as the [`DevQuitButton`] is pressed. Does nothing
+/// without that resource
+///
+/// # Examples
+/// ```
+/// use bevy::{app::AppExit, input::InputPlugin, prelude::*};
+/// use devcaders::{exit_on_dev_quit_button, Button, DevQuitButton, Player};
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .add_systems(Update, exit_on_dev_quit_button);
+///
+/// // Without a quit button, nothing quits
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::F);
+/// app.update();
+/// assert!(app.world.resource::<Events<AppExit>>().is_empty());
+/// app.world.resource_mut::<Input<KeyCode>>().release(KeyCode::F);
+/// app.update();
+///
+/// app.insert_resource(DevQuitButton::new(Player::P1, Button::B4));
+/// // Other buttons and players don't quit
+/// let mut input = app.world.resource_mut::<Input<KeyCode>>();
+/// input.press(KeyCode::D);
+/// input.press(KeyCode::L);
+/// app.update();
+/// assert!(app.world.resource::<Events<AppExit>>().is_empty());
+///
+/// // P1's B4 is bound to F on the keyboard
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::F);
+/// app.update();
+/// assert!(!app.world.resource::<Events<AppExit>>().is_empty());
+/// ```
+pub fn exit_on_dev_quit_button(
+  input: DevcadeControls,
+  quit: Option<Res<DevQuitButton>>,
+  mut exit: EventWriter<AppExit>,
+) {
+  if quit.is_some_and(|quit| input.just_pressed(quit.player, quit.button)) {
+    exit.send(AppExit);
+  }
+}
+
+/// The first player to press a button, inserted by [`wait_for_player_join`]
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinedPlayer(pub Player);
+
+/// Inserts [`JoinedPlayer`] as soon as any player presses any button.
+///
+/// Does nothing while [`JoinedPlayer`] is already present, so the first
+/// player to press something wins. Remove the resource to wait for the next
+/// player, for example when going back to the attract screen.
+///
+/// # Examples
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{wait_for_player_join, JoinedPlayer, Player};
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .add_systems(Update, wait_for_player_join);
+///
+/// app.update();
+/// assert!(app.world.get_resource::<JoinedPlayer>().is_none());
+///
+/// // P2 presses A1, then P1 presses A1 too late
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Y);
+/// app.update();
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Q);
+/// app.update();
+/// assert_eq!(app.world.get_resource(), Some(&JoinedPlayer(Player::P2)));
+///
+/// // Back to the attract screen
+/// app.world.remove_resource::<JoinedPlayer>();
+/// app.world.resource_mut::<Input<KeyCode>>().release_all();
+/// app.update();
+/// app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Q);
+/// app.update();
+/// assert_eq!(app.world.get_resource(), Some(&JoinedPlayer(Player::P1)));
+/// ```
+pub fn wait_for_player_join(
+  mut commands: Commands,
+  input: DevcadeControls,
+  joined: Option<Res<JoinedPlayer>>,
+) {
+  if joined.is_some() {
+    return;
+  }
+  let player = enum_iterator::all::<Player>()
+    .find(|&player| input.just_pressed_buttons(player).next().is_some());
+  if let Some(player) = player {
+    commands.insert_resource(JoinedPlayer(player));
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+struct CellWrapper<T>(OnceLock<T>);
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl<T> CellWrapper<T> {
+  const fn new() -> Self {
+    Self(OnceLock::new())
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl Deref for CellWrapper<Arc<BackendClient>> {
+  type Target = Arc<BackendClient>;
+  fn deref(&self) -> &Self::Target {
+    self.0.get_or_init(Self::Target::default)
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+static CLIENT: CellWrapper<Arc<BackendClient>> = CellWrapper::new();
+
+/// The [`BackendClient`] that devcaders' systems send their requests with
+///
+/// [`DevcadePlugin`] adds the default one, which shares the connection that
+/// [`NfcTagRequestComponent::new`] and friends use. Insert your own to point
+/// [`NfcPoller`] and [`BackendDiagnosticsPlugin`] somewhere else, like a fake
+/// backend in tests, and pass it to
+/// [`NfcTagRequestComponent::with_client`] or
+/// [`NfcUserRequestComponent::with_client`] from your own systems.
+///
+/// # Example
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*};
+/// use devcaders::{BackendClient, BackendClientResource, DevcadePlugin};
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+///   .insert_resource(BackendClientResource::new(BackendClient::with_path(
+///     "/tmp/my-fake-backend.sock",
+///   )));
+/// ```
+#[derive(Resource, Clone, Deref)]
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub struct BackendClientResource(pub Arc<BackendClient>);
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl BackendClientResource {
+  /// Wraps `client` so it can be inserted as a resource
+  pub fn new(client: BackendClient) -> Self {
+    Self(Arc::new(client))
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl Default for BackendClientResource {
+  /// The client shared with requests made without a [`BackendClientResource`]
+  fn default() -> Self {
+    Self(CLIENT.clone())
+  }
+}
+
+/// Which NFC reader belongs to each [`Player`]
+///
+/// Defaults to each player using the reader on their own side. Insert a
+/// different mapping if a cabinet's readers are wired the other way around,
+/// and pass it to [`NfcTagRequestComponent::for_player`].
+///
+/// # Example
+/// ```
+/// use devcaders::devcade_onboard_types::Player as BackendPlayer;
+/// use devcaders::{NfcReaderMapping, Player};
+///
+/// let readers = NfcReaderMapping::default();
+/// assert_eq!(readers.reader(Player::P1), BackendPlayer::P1);
+///
+/// let readers = NfcReaderMapping::swapped();
+/// assert_eq!(readers.reader(Player::P1), BackendPlayer::P2);
+/// assert_eq!(readers.reader(Player::P2), BackendPlayer::P1);
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub struct NfcReaderMapping {
+  /// Reader used by [`Player::P1`]
+  pub p1: BackendPlayer,
+  /// Reader used by [`Player::P2`]
+  pub p2: BackendPlayer,
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl Default for NfcReaderMapping {
+  fn default() -> Self {
+    Self {
+      p1: BackendPlayer::P1,
+      p2: BackendPlayer::P2,
+    }
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl NfcReaderMapping {
+  /// Mapping where each player uses the reader on the other player's side
+  pub fn swapped() -> Self {
+    Self {
+      p1: BackendPlayer::P2,
+      p2: BackendPlayer::P1,
+    }
+  }
+
+  /// Returns the reader that belongs to `player`
+  pub fn reader(&self, player: Player) -> BackendPlayer {
+    match player {
+      Player::P1 => self.p1.clone(),
+      Player::P2 => self.p2.clone(),
+    }
+  }
+}
+
+/// Represents an inflight request to the backend for NFC tags on the reader
+/// You can spawn an entity with this component to poll the request:
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use devcaders::NfcTagRequestComponent;
+/// #[derive(Component, Deref, DerefMut)]
+/// struct MyNfcTagRequest(NfcTagRequestComponent);
+/// fn nfc_system(mut commands: Commands, mut tags_request: Query<(&mut MyNfcTagRequest, Entity)>) {
+///   for (mut tags_request, id) in &mut tags_request {
+///     if let Some(tag) = tags_request.poll() {
+///       println!("Got a response! {tag:?}");
+///       commands.entity(id).despawn();
+///     }
+///   }
+///   if tags_request.is_empty() {
+///     println!("Creating a new request...");
+///     commands.spawn(MyNfcTagRequest(NfcTagRequestComponent::new()));
+///   }
+/// }
+/// ```
+#[derive(Component)]
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub struct NfcTagRequestComponent(Option<Task<Result<Option<AssociationId>, RequestError>>>);
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl Default for NfcTagRequestComponent {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+impl NfcTagRequestComponent {
+  /// Creates a new `NfcTagRequestComponent` for the first player's reader
+  pub fn new() -> Self {
+    Self::for_player(Player::P1, &NfcReaderMapping::default())
+  }
+
+  /// Creates a new `NfcTagRequestComponent` for the reader that `readers`
+  /// says belongs to `player`
+  ///
+  /// Both players' readers can be asked at once. A slow answer for one
+  /// player doesn't hold up the other.
+  ///
+  /// # Example
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{NfcReaderMapping, NfcTagRequestComponent, Player};
+  /// fn start_sign_in(mut commands: Commands, readers: Res<NfcReaderMapping>) {
+  ///   for player in [Player::P1, Player::P2] {
+  ///     commands.spawn(NfcTagRequestComponent::for_player(player, &readers));
+  ///   }
+  /// }
+  /// ```
+  pub fn for_player(player: Player, readers: &NfcReaderMapping) -> Self {
+    Self::spawn(CLIENT.clone(), readers.reader(player), None)
+  }
+
+  /// Like [`NfcTagRequestComponent::for_player`], but the request fails with
+  /// [`RequestError::Timeout`] if the backend hasn't answered within
+  /// `timeout`
+  ///
+  /// # Example
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{NfcReaderMapping, NfcTagRequestComponent, Player};
+  /// # use std::time::Duration;
+  /// fn start_sign_in(mut commands: Commands, readers: Res<NfcReaderMapping>) {
+  ///   let timeout = Duration::from_secs(2);
+  ///   commands.spawn(NfcTagRequestComponent::new_with_timeout(Player::P1, &readers, timeout));
+  /// }
+  /// ```
+  pub fn new_with_timeout(player: Player, readers: &NfcReaderMapping, timeout: Duration) -> Self {
+    Self::spawn(CLIENT.clone(), readers.reader(player), Some(timeout))
+  }
+
+  /// Like [`NfcTagRequestComponent::for_player`], but sends the request with
+  /// `client` instead of the shared client
+  pub fn with_client(
+    client: &BackendClientResource,
+    player: Player,
+    readers: &NfcReaderMapping,
+  ) -> Self {
+    Self::spawn(client.0.clone(), readers.reader(player), None)
+  }
+
+  fn spawn(client: Arc<BackendClient>, reader: BackendPlayer, timeout: Option<Duration>) -> Self {
     let pool = AsyncComputeTaskPool::get();
-    Self(pool.spawn(Compat::new(async move {
-      CLIENT
-        .send(RequestBody::GetNfcTag(BackendPlayer::P1))
-        .await
-        .and_then(|response_body| match response_body {
-          ResponseBody::NfcTag(tag_id) => Ok(tag_id),
-          body => Err(RequestError::UnexpectedResponse(body)),
-        })
-    })))
+    Self(Some(pool.spawn(Compat::new(async move {
+      get_nfc_tag(&client, reader, timeout).await
+    }))))
   }
   /// Check if this request has completed.
-  /// If it has, the return value will be `Some` with either the
-  /// assocation ID as a `String` or `None` if no tags were on the reader
-  pub fn poll(&mut self) -> Option<Result<Option<String>, RequestError>> {
-    future::block_on(future::poll_once(&mut self.0))
+  /// If it has, the return value will be `Some` with either the tag's
+  /// [`AssociationId`] or `None` if no tags were on the reader.
+  /// Once the request is [cancelled](NfcTagRequestComponent::cancel), this
+  /// returns [`RequestError::Cancelled`].
+  pub fn poll(&mut self) -> Option<Result<Option<AssociationId>, RequestError>> {
+    match &mut self.0 {
+      Some(task) => future::block_on(future::poll_once(task)),
+      None => Some(Err(RequestError::Cancelled)),
+    }
+  }
+
+  /// Stops waiting for the backend to answer, so a late answer is never
+  /// seen. Useful when switching which reader is being read.
+  ///
+  /// # Example
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::NfcTagRequestComponent;
+  /// fn leave_sign_in(mut requests: Query<&mut NfcTagRequestComponent>) {
+  ///   for mut request in &mut requests {
+  ///     request.cancel();
+  ///   }
+  /// }
+  /// ```
+  pub fn cancel(&mut self) {
+    self.0 = None;
+  }
+}
+
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+async fn get_nfc_tag(
+  client: &BackendClient,
+  reader: BackendPlayer,
+  timeout: Option<Duration>,
+) -> Result<Option<AssociationId>, RequestError> {
+  send_to_backend(client, RequestBody::GetNfcTag(reader), timeout)
+    .await
+    .and_then(ResponseBody::expect_nfc_tag)
+    .map(|tag_id| tag_id.map(AssociationId::from))
+}
+
+/// Sends `body` with `client`, giving up after `timeout` if there is one
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+async fn send_to_backend(
+  client: &BackendClient,
+  body: RequestBody,
+  timeout: Option<Duration>,
+) -> Result<ResponseBody, RequestError> {
+  match timeout {
+    Some(timeout) => client.send_timeout(body, timeout).await,
+    None => client.send(body).await,
   }
 }
 
+/// Stream that yields whenever a tag is placed on or removed from `player`'s
+/// NFC reader
+///
+/// Items are `Some` with the tag's association ID when a tag is placed and
+/// `None` when it's removed. The reader is polled every `interval`; failed
+/// polls are logged and retried on the next interval. See
+/// [`nfc_tag_transitions`] for exactly when items are yielded.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::tasks::AsyncComputeTaskPool;
+/// # use devcaders::{nfc_tag_changes, NfcReaderMapping, Player};
+/// # use futures_lite::StreamExt;
+/// # use std::time::Duration;
+/// fn start_watching_reader(readers: Res<NfcReaderMapping>) {
+///   let changes = nfc_tag_changes(Player::P1, &readers, Duration::from_millis(250));
+///   AsyncComputeTaskPool::get()
+///     .spawn(async move {
+///       let mut changes = std::pin::pin!(changes);
+///       while let Some(tag) = changes.next().await {
+///         println!("Tag changed! {tag:?}");
+///       }
+///     })
+///     .detach();
+/// }
+/// ```
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub fn nfc_tag_changes(
+  player: Player,
+  readers: &NfcReaderMapping,
+  interval: Duration,
+) -> impl Stream<Item = Option<AssociationId>> + Send {
+  let reader = readers.reader(player);
+  let polls = stream::unfold(false, move |polled| {
+    let reader = reader.clone();
+    Compat::new(async move {
+      if polled {
+        tokio::time::sleep(interval).await;
+      }
+      loop {
+        match get_nfc_tag(&CLIENT, reader.clone(), None).await {
+          Ok(tag_id) => return Some((tag_id, true)),
+          Err(err) => log::error!("Couldn't poll NFC reader {reader}: {err}"),
+        }
+        tokio::time::sleep(interval).await;
+      }
+    })
+  });
+  nfc_tag_transitions(polls)
+}
+
+/// Filters a stream of NFC reader states down to the states that differ from
+/// the previous one
+///
+/// The reader is assumed to start out empty, so leading `None`s are skipped.
+///
+/// # Example
+/// ```
+/// use devcaders::{nfc_tag_transitions, AssociationId};
+/// use futures_lite::{future, stream, StreamExt};
+///
+/// let states = [None, Some("a"), Some("a"), None, None, Some("b"), Some("c")];
+/// let states = stream::iter(states.map(|state| state.map(AssociationId::from)));
+/// let changes: Vec<_> = future::block_on(nfc_tag_transitions(states).collect());
+/// assert_eq!(
+///   changes,
+///   [Some("a"), None, Some("b"), Some("c")].map(|state| state.map(AssociationId::from))
+/// );
+/// ```
+pub fn nfc_tag_transitions(
+  states: impl Stream<Item = Option<AssociationId>>,
+) -> impl Stream<Item = Option<AssociationId>> {
+  let mut current = None;
+  states.filter_map(move |state| {
+    if state == current {
+      None
+    } else {
+      current = state.clone();
+      Some(state)
+    }
+  })
+}
+
 /// Represents an inflight request to the backend for the user associated with
 /// a particular NFC tag assocation id
 ///
 /// You can spawn an entity with this component to poll the request:
 /// # Example
 /// ```
+/// # use bevy::prelude::*;
+/// # use devcaders::{NfcTagRequestComponent, NfcUserRequestComponent};
 /// #[derive(Component, Deref, DerefMut)]
 /// struct MyNfcTagRequest(NfcTagRequestComponent);
 /// #[derive(Component, Deref, DerefMut)]
 /// struct MyNfcUserRequest(NfcUserRequestComponent);
 /// fn nfc_system(
 ///   mut commands: Commands,
-///   mut tags_request: Query<(&mut MyNfcTagRequest, Entity)>
+///   mut tags_request: Query<(&mut MyNfcTagRequest, Entity)>,
 ///   mut users_request: Query<(&mut MyNfcUserRequest, Entity)>
 /// ) {
 ///   for (mut tags_request, id) in &mut tags_request {
@@ -460,7 +2777,7 @@ impl NfcTagRequestComponent {
 ///       println!("Got a response! {tag:?}");
 ///       commands.entity(id).despawn();
 ///       if let Ok(Some(tag_id)) = tag {
-///         commands.spawn(MyNfcUserRequest(NfcUserRequestComponent::new(tag));
+///         commands.spawn(MyNfcUserRequest(NfcUserRequestComponent::new(tag_id)));
 ///       }
 ///     }
 ///   }
@@ -469,7 +2786,7 @@ impl NfcTagRequestComponent {
 ///       println!("Got a response! {user:?}");
 ///       commands.entity(id).despawn();
 ///       if let Ok(user) = user {
-///         println!("Username is: {}", user["uid"].as_str().unwrap());
+///         println!("Username is: {}", user.uid().unwrap());
 ///       }
 ///     }
 ///   }
@@ -480,29 +2797,841 @@ impl NfcTagRequestComponent {
 /// }
 /// ```
 #[derive(Component)]
-#[cfg(not(target_os = "windows"))]
-pub struct NfcUserRequestComponent(Task<Result<Map<String, Value>, RequestError>>);
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
+pub struct NfcUserRequestComponent(Task<Result<DevcadeUser, RequestError>>);
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(feature = "backend", not(target_os = "windows")))]
 impl NfcUserRequestComponent {
   /// Creates a new `NfcUserRequestComponent`
-  pub fn new(association_id: String) -> Self {
+  pub fn new(association_id: impl Into<AssociationId>) -> Self {
+    Self::spawn(CLIENT.clone(), association_id.into(), None)
+  }
+
+  /// Like [`NfcUserRequestComponent::new`], but sends the request with
+  /// `client` instead of the shared client
+  pub fn with_client(
+    client: &BackendClientResource,
+    association_id: impl Into<AssociationId>,
+  ) -> Self {
+    Self::spawn(client.0.clone(), association_id.into(), None)
+  }
+
+  /// Like [`NfcUserRequestComponent::new`], but the request fails with
+  /// [`RequestError::Timeout`] if the backend hasn't answered within
+  /// `timeout`
+  ///
+  /// # Example
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::NfcUserRequestComponent;
+  /// # use std::time::Duration;
+  /// fn look_up_user(mut commands: Commands) {
+  ///   let timeout = Duration::from_secs(2);
+  ///   commands.spawn(NfcUserRequestComponent::new_with_timeout("badge-1234", timeout));
+  /// }
+  /// ```
+  pub fn new_with_timeout(association_id: impl Into<AssociationId>, timeout: Duration) -> Self {
+    Self::spawn(CLIENT.clone(), association_id.into(), Some(timeout))
+  }
+
+  fn spawn(
+    client: Arc<BackendClient>,
+    association_id: AssociationId,
+    timeout: Option<Duration>,
+  ) -> Self {
     let pool = AsyncComputeTaskPool::get();
     Self(pool.spawn(Compat::new(async move {
-      CLIENT
-        .send(RequestBody::GetNfcUser(association_id))
+      let body = RequestBody::GetNfcUser(association_id.into());
+      send_to_backend(&client, body, timeout)
         .await
-        .and_then(|response_body| match response_body {
-          ResponseBody::NfcUser(value) => Ok(value),
-          body => Err(RequestError::UnexpectedResponse(body)),
-        })
+        .and_then(ResponseBody::expect_nfc_user)
+        .map(DevcadeUser::from)
     })))
   }
 
   /// Check if this request has completed.
-  /// If it has, the return value will be a `Result` with either list of the
-  /// user's attributes or a [`RequestError`] explaining why the request failed
-  pub fn poll(&mut self) -> Option<Result<Map<String, Value>, RequestError>> {
+  /// If it has, the return value will be a `Result` with either the user's
+  /// attributes or a [`RequestError`] explaining why the request failed
+  pub fn poll(&mut self) -> Option<Result<DevcadeUser, RequestError>> {
     future::block_on(future::poll_once(&mut self.0))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::fake_gamepad::{
+    connect_gamepad, controls_app, keyboard_app, press_key, release_key, set_axis, set_stick,
+    update_and_read, Read,
+  };
+  use crate::*;
+  use bevy::input::InputPlugin;
+  use bevy::time::TimeUpdateStrategy;
+
+  fn with_deadzone(deadzone: f32) -> DevcadeInputConfig {
+    DevcadeInputConfig {
+      deadzone,
+      ..default()
+    }
+  }
+
+  #[test]
+  fn stick_buttons_agree_with_the_stick() {
+    let (mut app, gamepad) = controls_app(with_deadzone(0.2), |input| {
+      (
+        input.stick(Player::P1),
+        input.pressed(Player::P1, Button::StickRight),
+        input.pressed(Player::P1, Button::StickLeft),
+      )
+    });
+    for x in [-1.0, -0.5, -0.2, -0.1, 0.0, 0.1, 0.2, 0.5, 1.0] {
+      set_stick(&mut app, gamepad, x, 0.0);
+      let (stick, right, left) = update_and_read::<(Vec2, bool, bool)>(&mut app);
+      assert_eq!(stick.x > 0.0, right);
+      assert_eq!(stick.x < 0.0, left);
+      // Inside the deadzone, neither counts
+      assert_eq!(right || left, x.abs() > 0.2);
+    }
+  }
+
+  #[test]
+  fn stick_intensity_grows_from_the_deadzone() {
+    let (mut app, gamepad) = controls_app(with_deadzone(0.2), |input| {
+      input.stick_intensity(Player::P1)
+    });
+    let mut intensity = |x, y| {
+      set_stick(&mut app, gamepad, x, y);
+      update_and_read::<f32>(&mut app)
+    };
+
+    assert_eq!(intensity(0.0, 0.0), 0.0);
+    // Inside the deadzone
+    assert_eq!(intensity(0.15, 0.0), 0.0);
+    // Deeper is stronger
+    let pushes = [0.4, 0.6, 0.8, 1.0].map(|x| intensity(x, 0.0));
+    assert!(pushes.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!((pushes[0] - 0.25).abs() < 1e-5);
+    assert_eq!(pushes[3], 1.0);
+    // Whichever axis is pushed furthest counts
+    assert!((intensity(0.3, -0.6) - 0.5).abs() < 1e-5);
+  }
+
+  #[test]
+  fn stick_direction_respects_the_deadzone() {
+    let (mut app, gamepad) = controls_app(with_deadzone(0.2), |input| {
+      input.stick_direction(Player::P1)
+    });
+    let mut direction = |x, y| {
+      set_stick(&mut app, gamepad, x, y);
+      update_and_read::<Option<Direction8>>(&mut app)
+    };
+    assert_eq!(direction(0.0, 0.0), None);
+    // Inside the deadzone
+    assert_eq!(direction(0.1, -0.15), None);
+    assert_eq!(direction(0.7, 0.7), Some(Direction8::NE));
+    assert_eq!(direction(0.1, -0.9), Some(Direction8::S));
+  }
+
+  #[test]
+  fn stick_facing_keeps_the_last_direction() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin));
+    let gamepad = Gamepad::new(0);
+    connect_gamepad(&mut app, gamepad);
+    assert_eq!(
+      app.world.resource::<StickFacing>().facing(Player::P1),
+      Direction8::S
+    );
+
+    let mut facing = |x, y| {
+      set_stick(&mut app, gamepad, x, y);
+      app.update();
+      app.world.resource::<StickFacing>().facing(Player::P1)
+    };
+    assert_eq!(facing(-0.8, 0.8), Direction8::NW);
+    // Letting go of the stick keeps facing the same way
+    assert_eq!(facing(0.0, 0.0), Direction8::NW);
+    assert_eq!(facing(0.0, 1.0), Direction8::N);
+    assert_eq!(facing(0.0, 0.0), Direction8::N);
+  }
+
+  #[test]
+  fn no_input_stops_once_a_controller_connects() {
+    #[derive(Resource, Default)]
+    struct NoInputSeen(bool);
+
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+      .insert_resource(DevcadeInputConfig {
+        keyboard_fallback: false,
+        ..default()
+      })
+      .init_resource::<NoInputSeen>()
+      .add_systems(
+        Update,
+        |mut no_input: EventReader<DevcadeNoInputAvailable>, mut seen: ResMut<NoInputSeen>| {
+          seen.0 = no_input.read().count() > 0;
+        },
+      );
+    app.update();
+    assert!(app.world.resource::<NoInputSeen>().0);
+
+    connect_gamepad(&mut app, Gamepad::new(0));
+    assert!(!app.world.resource::<NoInputSeen>().0);
+  }
+
+  #[test]
+  fn pressed_buttons_lists_held_buttons_in_order() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.pressed_buttons(Player::P1).collect::<Vec<_>>()
+    });
+    for key in [KeyCode::Q, KeyCode::F, KeyCode::N] {
+      press_key(&mut app, key);
+    }
+    assert_eq!(
+      update_and_read::<Vec<Button>>(&mut app),
+      [Button::A1, Button::B4, Button::StickRight]
+    );
+  }
+
+  #[test]
+  fn time_since_pressed_counts_from_the_last_press() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.time_since_pressed(Player::P1, Button::A1)
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    assert_eq!(update_and_read::<Option<Duration>>(&mut app), None);
+
+    press_key(&mut app, KeyCode::Q);
+    assert_eq!(
+      update_and_read::<Option<Duration>>(&mut app),
+      Some(Duration::ZERO)
+    );
+
+    // Still counting after it's released
+    release_key(&mut app, KeyCode::Q);
+    app.update();
+    assert_eq!(
+      update_and_read::<Option<Duration>>(&mut app),
+      Some(Duration::from_millis(200))
+    );
+
+    press_key(&mut app, KeyCode::Q);
+    assert_eq!(
+      update_and_read::<Option<Duration>>(&mut app),
+      Some(Duration::ZERO)
+    );
+  }
+
+  #[test]
+  fn gamepad_name_is_none_without_a_controller() {
+    let (mut app, _) = controls_app(DevcadeInputConfig::default(), |input| {
+      [Player::P1, Player::P2].map(|player| input.gamepad_name(player).map(str::to_owned))
+    });
+    assert_eq!(
+      update_and_read::<[Option<String>; 2]>(&mut app),
+      [Some("Gamepad 0".to_owned()), None]
+    );
+  }
+
+  #[cfg(feature = "debug-input-history")]
+  #[test]
+  fn input_history_keeps_the_last_frames() {
+    let config = DevcadeInputConfig {
+      input_history_len: 3,
+      ..default()
+    };
+    let mut app = keyboard_app(config, |input| {
+      input.input_history().cloned().collect::<Vec<_>>()
+    });
+    for _ in 0..4 {
+      app.update();
+    }
+    press_key(&mut app, KeyCode::Q);
+    let history = update_and_read::<Vec<InputSnapshot>>(&mut app);
+    let frames: Vec<_> = history.iter().map(|snapshot| snapshot.frame).collect();
+    assert_eq!(frames, [2, 3, 4]);
+    assert_eq!(history[1].pressed(Player::P1), []);
+    assert_eq!(history[2].pressed(Player::P1), [Button::A1]);
+  }
+
+  #[test]
+  fn consumed_presses_are_only_seen_once() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      [(); 2].map(|_| {
+        let jumped = input.just_pressed(Player::P1, Button::A1);
+        if jumped {
+          input.consume(Player::P1, Button::A1);
+        }
+        jumped
+      })
+    });
+    press_key(&mut app, KeyCode::Q);
+    assert_eq!(update_and_read::<[bool; 2]>(&mut app), [true, false]);
+  }
+
+  #[test]
+  fn any_gamepad_connected_counts_unassigned_controllers() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.any_gamepad_connected()
+    });
+    assert!(!update_and_read::<bool>(&mut app));
+    // Two players, so the third controller has nobody to go to
+    for id in 0..3 {
+      connect_gamepad(&mut app, Gamepad::new(id));
+      assert!(update_and_read::<bool>(&mut app));
+    }
+  }
+
+  #[test]
+  fn double_taps_pair_up_within_the_window() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      let window = Duration::from_millis(200);
+      input.just_double_tapped(Player::P1, Button::StickRight, window)
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    // Taps 100ms long, with `gap` between them. Returns whether each dashed
+    let tap = |app: &mut App, gap: u32| {
+      for _ in 1..gap / 100 {
+        app.update();
+      }
+      press_key(app, KeyCode::N);
+      let dashed = update_and_read::<bool>(app);
+      release_key(app, KeyCode::N);
+      app.update();
+      dashed
+    };
+
+    assert!(!tap(&mut app, 1000));
+    // 200ms between presses is just quick enough
+    assert!(tap(&mut app, 100));
+    // The third tap doesn't dash again, but the fourth does
+    assert!(!tap(&mut app, 100));
+    assert!(tap(&mut app, 100));
+
+    // 300ms between presses is too slow
+    assert!(!tap(&mut app, 1000));
+    assert!(!tap(&mut app, 200));
+  }
+
+  #[test]
+  fn gamepad_assignments_lists_every_controller() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.gamepad_assignments().to_vec()
+    });
+    for id in 0..3 {
+      connect_gamepad(&mut app, Gamepad::new(id));
+    }
+    let assignments = update_and_read::<Vec<GamepadAssignment>>(&mut app);
+    let summary: Vec<_> = assignments
+      .iter()
+      .map(|assignment| {
+        (
+          assignment.gamepad.id,
+          assignment.name.as_str(),
+          assignment.player,
+        )
+      })
+      .collect();
+    assert_eq!(
+      summary,
+      [
+        (0, "Gamepad 0", Some(Player::P1)),
+        (1, "Gamepad 1", Some(Player::P2)),
+        (2, "Gamepad 2", None),
+      ]
+    );
+  }
+
+  #[test]
+  fn menu_pauses_on_press_and_quits_when_all_hold() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (input.menu_pressed_any(), input.menu_held_by_all())
+    });
+    let mut bindings = KeyboardBindings::default();
+    bindings.bind(Player::P2, Button::Menu, KeyCode::P);
+    app.insert_resource(bindings);
+
+    // One player pauses, and holding doesn't pause again
+    press_key(&mut app, KeyCode::Escape);
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, false));
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (false, false));
+
+    // The other joins in to quit
+    press_key(&mut app, KeyCode::P);
+    assert_eq!(update_and_read::<(bool, bool)>(&mut app), (true, true));
+  }
+
+  #[test]
+  fn was_pressed_last_frame_lags_one_frame() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (
+        input.pressed(Player::P1, Button::A1),
+        input.was_pressed_last_frame(Player::P1, Button::A1),
+      )
+    });
+    let held = [false, true, true, false, true, false, false];
+    let mut states = Vec::new();
+    for pressed in held {
+      if pressed {
+        press_key(&mut app, KeyCode::Q);
+      } else {
+        release_key(&mut app, KeyCode::Q);
+      }
+      states.push(update_and_read::<(bool, bool)>(&mut app));
+    }
+
+    assert_eq!(states[0], (false, false));
+    for (frame, &(pressed, was_pressed)) in states.iter().enumerate().skip(1) {
+      assert_eq!(pressed, held[frame]);
+      assert_eq!(was_pressed, held[frame - 1]);
+    }
+  }
+
+  #[test]
+  fn active_players_pressed_something_recently() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.active_players(Duration::from_millis(300))
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    assert_eq!(update_and_read::<Vec<Player>>(&mut app), []);
+
+    // P2 taps a button, and P1 stays idle
+    press_key(&mut app, KeyCode::Y);
+    app.update();
+    release_key(&mut app, KeyCode::Y);
+    for _ in 0..3 {
+      assert_eq!(update_and_read::<Vec<Player>>(&mut app), [Player::P2]);
+    }
+    assert_eq!(update_and_read::<Vec<Player>>(&mut app), []);
+
+    // Holding a button keeps a player active
+    press_key(&mut app, KeyCode::Q);
+    for _ in 0..5 {
+      assert_eq!(update_and_read::<Vec<Player>>(&mut app), [Player::P1]);
+    }
+  }
+
+  #[test]
+  fn input_set_orders_pre_update_systems() {
+    #[derive(Resource, Default)]
+    struct Pressed(bool);
+
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+      .init_resource::<Pressed>()
+      .add_systems(
+        PreUpdate,
+        (|input: DevcadeControls, mut pressed: ResMut<Pressed>| {
+          pressed.0 = input.pressed(Player::P1, Button::A1);
+        })
+        .after(DevcadeInputSet),
+      );
+
+    app
+      .world
+      .resource_mut::<InputOverride>()
+      .press_for_frames(Player::P1, Button::A1, 1);
+    app.update();
+    assert!(app.world.resource::<Pressed>().0);
+    app.update();
+    assert!(!app.world.resource::<Pressed>().0);
+  }
+
+  #[test]
+  fn any_changed_this_frame_sees_presses_and_releases() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.any_changed_this_frame()
+    });
+    assert!(!update_and_read::<bool>(&mut app));
+
+    press_key(&mut app, KeyCode::J);
+    assert!(update_and_read::<bool>(&mut app));
+    // Holding the button steady
+    assert!(!update_and_read::<bool>(&mut app));
+
+    release_key(&mut app, KeyCode::J);
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn nothing_is_pressed_without_the_input_plugin() {
+    let mut app = App::new();
+    app
+      .add_plugins(MinimalPlugins)
+      .init_resource::<Read<bool>>()
+      .add_systems(
+        Update,
+        |input: DevcadeControls, mut pressed: ResMut<Read<bool>>| {
+          pressed.0 = input.pressed_by_any(Button::A1) || input.any_gamepad_connected();
+        },
+      );
+    app.update();
+    assert!(!app.world.resource::<Read<bool>>().0);
+  }
+
+  #[test]
+  fn snapshot_matches_pressed_and_stick() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      Some(input.snapshot())
+    });
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    press_key(&mut app, KeyCode::N);
+    press_key(&mut app, KeyCode::Down);
+    let snapshot = update_and_read::<Option<InputSnapshot>>(&mut app).unwrap();
+    assert_eq!(snapshot.frame, 1);
+    assert_eq!(
+      snapshot.pressed(Player::P1),
+      [Button::A1, Button::StickRight]
+    );
+    assert_eq!(snapshot.stick(Player::P1), Vec2::X);
+    assert_eq!(snapshot.pressed(Player::P2), [Button::StickDown]);
+    assert_eq!(snapshot.stick(Player::P2), Vec2::NEG_Y);
+  }
+
+  #[test]
+  fn last_changed_orders_same_frame_changes_by_player() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| input.last_changed());
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    type Changed = Option<(Player, Button, bool, Duration)>;
+    assert_eq!(update_and_read::<Changed>(&mut app), None);
+
+    press_key(&mut app, KeyCode::Q);
+    press_key(&mut app, KeyCode::Y);
+    assert_eq!(
+      update_and_read::<Changed>(&mut app),
+      Some((Player::P2, Button::A1, true, Duration::ZERO))
+    );
+    assert_eq!(
+      update_and_read::<Changed>(&mut app),
+      Some((Player::P2, Button::A1, true, Duration::from_millis(100)))
+    );
+
+    release_key(&mut app, KeyCode::Q);
+    assert_eq!(
+      update_and_read::<Changed>(&mut app),
+      Some((Player::P1, Button::A1, false, Duration::ZERO))
+    );
+  }
+
+  #[test]
+  fn any_a_and_any_b_cover_their_rows() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      [
+        input.any_a_pressed(Player::P1),
+        input.any_a_just_pressed(Player::P1),
+        input.any_b_pressed(Player::P1),
+        input.any_b_just_pressed(Player::P1),
+      ]
+    });
+
+    // A3
+    press_key(&mut app, KeyCode::E);
+    assert_eq!(
+      update_and_read::<[bool; 4]>(&mut app),
+      [true, true, false, false]
+    );
+
+    // Still holding A3, and now B4
+    press_key(&mut app, KeyCode::F);
+    assert_eq!(
+      update_and_read::<[bool; 4]>(&mut app),
+      [true, false, true, true]
+    );
+
+    // P2's buttons don't count
+    release_key(&mut app, KeyCode::E);
+    release_key(&mut app, KeyCode::F);
+    press_key(&mut app, KeyCode::Y);
+    assert_eq!(update_and_read::<[bool; 4]>(&mut app), [false; 4]);
+  }
+
+  #[test]
+  fn stuck_buttons_are_reported_once_per_episode() {
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+      .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        250,
+      )))
+      .insert_resource(StuckButtonConfig {
+        threshold: Duration::from_secs(1),
+      })
+      .init_resource::<Read<Vec<DevcadeStuckButton>>>()
+      .add_systems(
+        Update,
+        |mut stuck: EventReader<DevcadeStuckButton>,
+         mut seen: ResMut<Read<Vec<DevcadeStuckButton>>>| {
+          seen.0.extend(stuck.read().copied());
+        },
+      );
+    let seen = |app: &App| {
+      app
+        .world
+        .resource::<Read<Vec<DevcadeStuckButton>>>()
+        .0
+        .clone()
+    };
+    let stuck = DevcadeStuckButton {
+      player: Player::P1,
+      button: Button::A1,
+    };
+
+    // Held for a second, and then some
+    press_key(&mut app, KeyCode::Q);
+    for _ in 0..4 {
+      app.update();
+      assert_eq!(seen(&app), []);
+    }
+    for _ in 0..8 {
+      app.update();
+      assert_eq!(seen(&app), [stuck]);
+    }
+
+    // Sticking again after being let go is a new episode
+    release_key(&mut app, KeyCode::Q);
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    for _ in 0..5 {
+      app.update();
+    }
+    assert_eq!(seen(&app), [stuck, stuck]);
+  }
+
+  #[test]
+  fn holding_or_releasing_keeps_input_from_idling() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.is_idle(Duration::from_millis(300))
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    assert!(update_and_read::<bool>(&mut app));
+
+    // Holding a button is never idle
+    press_key(&mut app, KeyCode::Q);
+    for _ in 0..5 {
+      assert!(!update_and_read::<bool>(&mut app));
+    }
+
+    // Letting go counts as input too
+    release_key(&mut app, KeyCode::Q);
+    for _ in 0..4 {
+      assert!(!update_and_read::<bool>(&mut app));
+    }
+    assert!(update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn right_stick_has_its_own_deadzoned_axes() {
+    let (mut app, gamepad) = controls_app(with_deadzone(0.2), |input| {
+      (input.stick(Player::P1), input.right_stick(Player::P1))
+    });
+    set_axis(&mut app, gamepad, GamepadAxisType::RightStickX, 0.5);
+    set_axis(&mut app, gamepad, GamepadAxisType::RightStickY, -1.0);
+    assert_eq!(
+      update_and_read::<(Vec2, Vec2)>(&mut app),
+      (Vec2::ZERO, Vec2::new(0.5, -1.0))
+    );
+
+    // Inside the deadzone
+    set_axis(&mut app, gamepad, GamepadAxisType::RightStickX, 0.1);
+    set_axis(&mut app, gamepad, GamepadAxisType::RightStickY, 0.0);
+    assert_eq!(update_and_read::<(Vec2, Vec2)>(&mut app).1, Vec2::ZERO);
+  }
+
+  #[test]
+  fn held_skips_the_frame_of_the_press() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.held(Player::P1, Button::A1)
+    });
+    press_key(&mut app, KeyCode::Q);
+    assert!(!update_and_read::<bool>(&mut app));
+    for _ in 0..3 {
+      assert!(update_and_read::<bool>(&mut app));
+    }
+
+    release_key(&mut app, KeyCode::Q);
+    assert!(!update_and_read::<bool>(&mut app));
+  }
+
+  #[test]
+  fn stick_nav_repeats_after_the_delay() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      input.stick_nav_just_pressed(Player::P1, Button::StickDown)
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      50,
+    )));
+    app.update();
+
+    // Held for 600ms with the default 400ms delay and 100ms repeat
+    press_key(&mut app, KeyCode::B);
+    let held_for: Vec<_> = (0..=12)
+      .filter(|_| update_and_read::<bool>(&mut app))
+      .map(|frame| frame * 50)
+      .collect();
+    assert_eq!(held_for, [0, 400, 500, 600]);
+  }
+
+  #[cfg(feature = "debug-input-history")]
+  #[test]
+  fn input_history_display_shows_motions_and_presses() {
+    let mut app = keyboard_app(DevcadeInputConfig::default(), |input| {
+      (
+        input.input_history_display(Player::P1, Duration::from_secs(1)),
+        input.input_history_display(Player::P1, Duration::from_millis(250)),
+      )
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+      100,
+    )));
+    app.update();
+
+    // A quarter circle forward and punch
+    press_key(&mut app, KeyCode::B);
+    app.update();
+    press_key(&mut app, KeyCode::N);
+    app.update();
+    press_key(&mut app, KeyCode::Q);
+    release_key(&mut app, KeyCode::B);
+    app.update();
+    release_key(&mut app, KeyCode::N);
+    release_key(&mut app, KeyCode::Q);
+    let (all, recent) = update_and_read::<(String, String)>(&mut app);
+    assert_eq!(all, "↓ ↘ → + A1");
+    // The first press is too long ago
+    assert_eq!(recent, "↘ → + A1");
+  }
+
+  #[cfg(all(feature = "backend", not(target_os = "windows")))]
+  mod backend {
+    use crate::test_backend::TestBackend;
+    use crate::*;
+    use bevy::input::InputPlugin;
+    use bevy::tasks::TaskPool;
+
+    /// Calls `poll` until it has an answer
+    fn wait_for<T>(mut poll: impl FnMut() -> Option<T>) -> T {
+      loop {
+        if let Some(result) = poll() {
+          return result;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+      }
+    }
+
+    #[test]
+    fn nfc_poller_uses_client_resource() {
+      let backend = TestBackend::respond(|_| ResponseBody::NfcTag(Some("custom".to_owned())));
+      let mut app = App::new();
+      app
+        .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+        .insert_resource(BackendClientResource::new(backend.client()));
+      app
+        .world
+        .resource_mut::<NfcPoller>()
+        .start_polling(Player::P1, Duration::from_secs(1));
+
+      while app.world.resource::<NfcPoller>().latest().is_none() {
+        app.update();
+        std::thread::sleep(Duration::from_millis(10));
+      }
+      let latest = app.world.resource::<NfcPoller>().latest();
+      assert!(matches!(latest, Some(Ok(Some(tag))) if *tag == "custom"));
+    }
+
+    #[test]
+    fn nfc_tag_requests_for_each_player_run_at_once() {
+      // A backend whose first reader takes a while to answer
+      let backend = TestBackend::serve(|request, connection| {
+        let RequestBody::GetNfcTag(reader) = &request.body else {
+          panic!("Unexpected request {request:?}");
+        };
+        let delay = match reader {
+          BackendPlayer::P1 => Duration::from_millis(300),
+          BackendPlayer::P2 => Duration::ZERO,
+        };
+        let tag = format!("{reader:?}");
+        let connection = connection.clone();
+        std::thread::spawn(move || {
+          std::thread::sleep(delay);
+          connection.reply(&request, ResponseBody::NfcTag(Some(tag)));
+        });
+      });
+
+      AsyncComputeTaskPool::get_or_init(TaskPool::new);
+      let client = BackendClientResource::new(backend.client());
+      let readers = NfcReaderMapping::default();
+      let mut p1 = NfcTagRequestComponent::with_client(&client, Player::P1, &readers);
+      let mut p2 = NfcTagRequestComponent::with_client(&client, Player::P2, &readers);
+
+      let p2_tag = wait_for(|| {
+        let result = p2.poll();
+        if result.is_none() {
+          assert!(p1.poll().is_none(), "P1 answered before P2");
+        }
+        result
+      });
+      assert_eq!(p2_tag.unwrap().as_deref(), Some("P2"));
+      let p1_tag = wait_for(|| p1.poll());
+      assert_eq!(p1_tag.unwrap().as_deref(), Some("P1"));
+    }
+
+    #[test]
+    fn nfc_tag_request_times_out() {
+      // A backend that never answers
+      let backend = TestBackend::serve(|_, _| {});
+      AsyncComputeTaskPool::get_or_init(TaskPool::new);
+      let timeout = Duration::from_millis(100);
+      let started = std::time::Instant::now();
+      let client = Arc::new(backend.client());
+      let mut request = NfcTagRequestComponent::spawn(client, BackendPlayer::P1, Some(timeout));
+      let result = wait_for(|| request.poll());
+      assert!(matches!(result, Err(RequestError::Timeout { .. })));
+      assert!(started.elapsed() >= timeout);
+    }
+
+    #[test]
+    fn cancelled_nfc_tag_request_stays_cancelled() {
+      // A backend that never answers
+      let backend = TestBackend::serve(|_, _| {});
+      AsyncComputeTaskPool::get_or_init(TaskPool::new);
+      let client = BackendClientResource::new(backend.client());
+      let readers = NfcReaderMapping::default();
+      let mut request = NfcTagRequestComponent::with_client(&client, Player::P1, &readers);
+      assert!(request.poll().is_none());
+
+      request.cancel();
+      assert!(matches!(request.poll(), Some(Err(RequestError::Cancelled))));
+      assert!(matches!(request.poll(), Some(Err(RequestError::Cancelled))));
+    }
+
+    #[test]
+    fn nfc_user_request_times_out() {
+      // A backend that never answers
+      let backend = TestBackend::serve(|_, _| {});
+      AsyncComputeTaskPool::get_or_init(TaskPool::new);
+      let timeout = Duration::from_millis(100);
+      let client = Arc::new(backend.client());
+      let mut request =
+        NfcUserRequestComponent::spawn(client, AssociationId::from("badge-1234"), Some(timeout));
+      let result = wait_for(|| request.poll());
+      let Err(RequestError::Timeout { waited, request }) = result else {
+        panic!("Expected a timeout");
+      };
+      assert_eq!(waited, timeout);
+      assert!(matches!(*request, RequestBody::GetNfcUser(id) if id == "badge-1234"));
+    }
+  }
+}
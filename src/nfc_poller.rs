@@ -0,0 +1,179 @@
+use crate::{
+  AssociationId, BackendClientResource, NfcReaderMapping, NfcTagRequestComponent, Player,
+  RequestError,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Asks a player's NFC reader for its tag every so often, so games don't each
+/// need their own spawn/poll/despawn loop
+///
+/// [`DevcadePlugin`](crate::DevcadePlugin) adds this and sends the requests
+/// with the [`BackendClientResource`].
+/// Nothing is polled until [`NfcPoller::start_polling`] is called, so a game
+/// can poll quickly on its sign-in screen and
+/// [stop](NfcPoller::stop_polling) during gameplay to spare the backend.
+///
+/// Only one request is in flight at a time. If the backend is slower than the
+/// interval, the next request is sent as soon as it answers.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{NfcPoller, Player};
+/// use std::time::Duration;
+///
+/// fn enter_sign_in(mut poller: ResMut<NfcPoller>) {
+///   poller.start_polling(Player::P1, Duration::from_millis(250));
+/// }
+///
+/// fn sign_in(poller: Res<NfcPoller>) {
+///   if let Some(Ok(Some(tag))) = poller.latest() {
+///     println!("Signed in with {tag}");
+///   }
+/// }
+///
+/// fn leave_sign_in(mut poller: ResMut<NfcPoller>) {
+///   poller.stop_polling();
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct NfcPoller {
+  polling: Option<Polling>,
+  latest: Option<Result<Option<AssociationId>, RequestError>>,
+}
+
+struct Polling {
+  player: Player,
+  timer: Timer,
+  /// Whether a request should be sent as soon as none is in flight
+  due: bool,
+  request: Option<NfcTagRequestComponent>,
+}
+
+impl NfcPoller {
+  /// Starts asking `player`'s reader for its tag every `interval`, beginning
+  /// on the next frame
+  ///
+  /// Replaces whatever was being polled before and forgets its
+  /// [latest](NfcPoller::latest) answer.
+  pub fn start_polling(&mut self, player: Player, interval: Duration) {
+    self.polling = Some(Polling {
+      player,
+      timer: Timer::new(interval, TimerMode::Repeating),
+      due: true,
+      request: None,
+    });
+    self.latest = None;
+  }
+
+  /// Stops polling, dropping any request that hasn't been answered yet. The
+  /// [latest](NfcPoller::latest) answer is kept
+  pub fn stop_polling(&mut self) {
+    self.polling = None;
+  }
+
+  /// Returns the player whose reader is being polled, if any
+  pub fn polling(&self) -> Option<Player> {
+    self.polling.as_ref().map(|polling| polling.player)
+  }
+
+  /// Returns the reader's most recent answer: the tag's association id, or
+  /// `None` if no tag was on the reader
+  pub fn latest(&self) -> Option<&Result<Option<AssociationId>, RequestError>> {
+    self.latest.as_ref()
+  }
+}
+
+/// Collects answers and sends requests for the [`NfcPoller`]
+pub(crate) fn poll_nfc(
+  time: Res<Time>,
+  client: Res<BackendClientResource>,
+  readers: Res<NfcReaderMapping>,
+  mut poller: ResMut<NfcPoller>,
+) {
+  let poller = &mut *poller;
+  let Some(polling) = &mut poller.polling else {
+    return;
+  };
+  if let Some(request) = &mut polling.request {
+    if let Some(result) = request.poll() {
+      if let Err(err) = &result {
+        log::debug!("Couldn't poll NFC reader for {:?}. {err}", polling.player);
+      }
+      poller.latest = Some(result);
+      polling.request = None;
+    }
+  }
+  if polling.timer.tick(time.delta()).just_finished() {
+    polling.due = true;
+  }
+  if polling.due && polling.request.is_none() {
+    polling.request = Some(NfcTagRequestComponent::with_client(
+      &client,
+      polling.player,
+      &readers,
+    ));
+    polling.due = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_backend::TestBackend;
+  use crate::DevcadePlugin;
+  use bevy::{input::InputPlugin, time::TimeUpdateStrategy};
+  use devcade_onboard_types::ResponseBody;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  #[test]
+  fn polls_on_interval_until_stopped() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let backend = TestBackend::serve({
+      let requests = requests.clone();
+      move |request, connection| {
+        requests.fetch_add(1, Ordering::SeqCst);
+        connection.reply(&request, ResponseBody::NfcTag(Some("badge".to_owned())));
+      }
+    });
+
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+      .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+      )))
+      .insert_resource(BackendClientResource::new(backend.client()));
+    let update = |app: &mut App| {
+      app.update();
+      std::thread::sleep(Duration::from_millis(50));
+      requests.load(Ordering::SeqCst)
+    };
+
+    // Nothing is polled until asked
+    assert_eq!(update(&mut app), 0);
+
+    // Polls right away, then every 300ms
+    app
+      .world
+      .resource_mut::<NfcPoller>()
+      .start_polling(Player::P1, Duration::from_millis(300));
+    assert_eq!(update(&mut app), 1);
+    assert_eq!(update(&mut app), 1);
+    assert_eq!(update(&mut app), 2);
+    for _ in 0..5 {
+      update(&mut app);
+    }
+    assert_eq!(update(&mut app), 4);
+    let latest = app.world.resource::<NfcPoller>().latest();
+    assert!(matches!(latest, Some(Ok(Some(tag))) if *tag == "badge"));
+
+    // Stopping stops the requests
+    app.world.resource_mut::<NfcPoller>().stop_polling();
+    for _ in 0..6 {
+      assert_eq!(update(&mut app), 4);
+    }
+  }
+}
@@ -0,0 +1,213 @@
+use bevy::prelude::*;
+
+/// Buttons bevy has names for, in the order they're reported
+const BUTTON_TYPES: [GamepadButtonType; 19] = [
+  GamepadButtonType::South,
+  GamepadButtonType::East,
+  GamepadButtonType::North,
+  GamepadButtonType::West,
+  GamepadButtonType::C,
+  GamepadButtonType::Z,
+  GamepadButtonType::LeftTrigger,
+  GamepadButtonType::LeftTrigger2,
+  GamepadButtonType::RightTrigger,
+  GamepadButtonType::RightTrigger2,
+  GamepadButtonType::Select,
+  GamepadButtonType::Start,
+  GamepadButtonType::Mode,
+  GamepadButtonType::LeftThumb,
+  GamepadButtonType::RightThumb,
+  GamepadButtonType::DPadUp,
+  GamepadButtonType::DPadDown,
+  GamepadButtonType::DPadLeft,
+  GamepadButtonType::DPadRight,
+];
+
+/// Axes bevy has names for, in the order they're reported
+const AXIS_TYPES: [GamepadAxisType; 6] = [
+  GamepadAxisType::LeftStickX,
+  GamepadAxisType::LeftStickY,
+  GamepadAxisType::LeftZ,
+  GamepadAxisType::RightStickX,
+  GamepadAxisType::RightStickY,
+  GamepadAxisType::RightZ,
+];
+
+/// Everything a gamepad's hardware reports, without Devcade's
+/// [`Button`](crate::Button) mapping, deadzone or any other
+/// [`DevcadeInputConfig`](crate::DevcadeInputConfig) setting applied
+///
+/// Meant for input test and calibration screens. Games should read
+/// [`DevcadeControls`](crate::DevcadeControls) instead.
+///
+/// # Examples
+/// ```
+/// # use bevy::prelude::*;
+/// # use devcaders::RawGamepadReadout;
+/// fn calibration_screen(
+///   gamepads: Res<Gamepads>,
+///   button_inputs: Res<Input<GamepadButton>>,
+///   button_axes: Res<Axis<GamepadButton>>,
+///   axes: Res<Axis<GamepadAxis>>,
+/// ) {
+///   for readout in RawGamepadReadout::read_all(&gamepads, &button_inputs, &button_axes, &axes) {
+///     println!("{:?}: {:?}", readout.gamepad, readout.axes);
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawGamepadReadout {
+  /// The gamepad that was read
+  pub gamepad: Gamepad,
+  /// Every button bevy has a name for, followed by any
+  /// [`Other`](GamepadButtonType::Other) buttons the gamepad has reported
+  pub buttons: Vec<RawButtonReading>,
+  /// Every axis bevy has a name for, followed by any
+  /// [`Other`](GamepadAxisType::Other) axes the gamepad has reported, and
+  /// where each one is. `None` if the gamepad hasn't reported the axis
+  pub axes: Vec<(GamepadAxisType, Option<f32>)>,
+}
+
+/// One button in a [`RawGamepadReadout`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawButtonReading {
+  /// The button
+  pub button_type: GamepadButtonType,
+  /// Whether bevy considers it pressed
+  pub pressed: bool,
+  /// How far it's pressed, from 0 to 1, for analog buttons like triggers.
+  /// `None` if the gamepad hasn't reported a value
+  pub value: Option<f32>,
+}
+
+impl RawGamepadReadout {
+  /// Reads `gamepad` from bevy's input resources
+  pub fn read(
+    gamepad: Gamepad,
+    button_inputs: &Input<GamepadButton>,
+    button_axes: &Axis<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+  ) -> Self {
+    let mut button_types = BUTTON_TYPES.to_vec();
+    let reported_buttons = button_inputs
+      .get_pressed()
+      .chain(button_axes.devices())
+      .filter(|button| button.gamepad == gamepad)
+      .map(|button| button.button_type);
+    for button_type in reported_buttons {
+      if !button_types.contains(&button_type) {
+        button_types.push(button_type);
+      }
+    }
+
+    let mut axis_types = AXIS_TYPES.to_vec();
+    let reported_axes = axes
+      .devices()
+      .filter(|axis| axis.gamepad == gamepad)
+      .map(|axis| axis.axis_type);
+    for axis_type in reported_axes {
+      if !axis_types.contains(&axis_type) {
+        axis_types.push(axis_type);
+      }
+    }
+
+    Self {
+      gamepad,
+      buttons: button_types
+        .into_iter()
+        .map(|button_type| {
+          let button = GamepadButton::new(gamepad, button_type);
+          RawButtonReading {
+            button_type,
+            pressed: button_inputs.pressed(button),
+            value: button_axes.get_unclamped(button),
+          }
+        })
+        .collect(),
+      axes: axis_types
+        .into_iter()
+        .map(|axis_type| {
+          let axis = GamepadAxis::new(gamepad, axis_type);
+          (axis_type, axes.get_unclamped(axis))
+        })
+        .collect(),
+    }
+  }
+
+  /// Reads every connected gamepad, ordered by id
+  pub fn read_all(
+    gamepads: &Gamepads,
+    button_inputs: &Input<GamepadButton>,
+    button_axes: &Axis<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+  ) -> Vec<Self> {
+    let mut gamepads: Vec<_> = gamepads.iter().collect();
+    gamepads.sort_by_key(|gamepad| gamepad.id);
+    gamepads
+      .into_iter()
+      .map(|gamepad| Self::read(gamepad, button_inputs, button_axes, axes))
+      .collect()
+  }
+
+  /// Returns where `axis_type` is, if the gamepad has reported it
+  pub fn axis(&self, axis_type: GamepadAxisType) -> Option<f32> {
+    self
+      .axes
+      .iter()
+      .find(|(reported, _)| *reported == axis_type)
+      .and_then(|(_, value)| *value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fake_gamepad::{connect_gamepad, press_gamepad_button, set_axis};
+  use bevy::input::InputPlugin;
+
+  #[test]
+  fn readout_reports_buttons_and_axes() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin));
+    let gamepad = Gamepad::new(0);
+    connect_gamepad(&mut app, gamepad);
+    press_gamepad_button(&mut app, gamepad, GamepadButtonType::Mode);
+    press_gamepad_button(&mut app, gamepad, GamepadButtonType::Other(42));
+    let trigger = GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2);
+    app
+      .world
+      .resource_mut::<Axis<GamepadButton>>()
+      .set(trigger, 0.25);
+    set_axis(&mut app, gamepad, GamepadAxisType::RightStickY, -0.5);
+
+    let world = &app.world;
+    let readouts = RawGamepadReadout::read_all(
+      world.resource(),
+      world.resource(),
+      world.resource(),
+      world.resource(),
+    );
+    assert_eq!(readouts.len(), 1);
+    let readout = &readouts[0];
+    assert_eq!(readout.gamepad, gamepad);
+
+    let pressed: Vec<_> = readout
+      .buttons
+      .iter()
+      .filter(|button| button.pressed)
+      .map(|button| button.button_type)
+      .collect();
+    assert_eq!(
+      pressed,
+      [GamepadButtonType::Mode, GamepadButtonType::Other(42)]
+    );
+    let trigger = readout
+      .buttons
+      .iter()
+      .find(|button| button.button_type == GamepadButtonType::LeftTrigger2);
+    assert_eq!(trigger.unwrap().value, Some(0.25));
+
+    assert_eq!(readout.axis(GamepadAxisType::RightStickY), Some(-0.5));
+    assert_eq!(readout.axis(GamepadAxisType::LeftZ), Some(0.0));
+  }
+}
@@ -0,0 +1,133 @@
+use crate::{InputOverride, InputSnapshot, Player};
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use std::time::Duration;
+
+/// Plays recorded input back into an [`App`], one recorded frame per update,
+/// so a game's real systems can be run against a recorded session in tests
+///
+/// Frames are recorded with
+/// [`DevcadeControls::snapshot`](crate::DevcadeControls::snapshot), or loaded
+/// from a file with the `serialize` feature. Each step presses the frame's
+/// buttons through [`InputOverride`] and updates the app once, with time
+/// advanced by as much as it was between the recorded frames. The app needs
+/// [`DevcadePlugin`](crate::DevcadePlugin) for the presses to take effect.
+///
+/// Stick directions are replayed as pressed or not, so an analog stick
+/// pushed part way is replayed as pushed all the way. Live input isn't
+/// blocked, so replays are best run in apps without real controllers.
+///
+/// # Examples
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*, time::TimeUpdateStrategy};
+/// use devcaders::{Button, DevcadeControls, DevcadePlugin, InputReplay, InputSnapshot, Player};
+/// use std::time::Duration;
+///
+/// #[derive(Resource, Default)]
+/// struct Recording(Vec<InputSnapshot>);
+///
+/// fn record(input: DevcadeControls, mut recording: ResMut<Recording>) {
+///   recording.0.push(input.snapshot());
+/// }
+///
+/// #[derive(Resource, Default)]
+/// struct Game {
+///   jumps: u32,
+///   x: f32,
+/// }
+///
+/// fn play(input: DevcadeControls, mut game: ResMut<Game>) {
+///   if input.just_pressed(Player::P1, Button::A1) {
+///     game.jumps += 1;
+///   }
+///   game.x += input.stick(Player::P1).x;
+/// }
+///
+/// // Record a short session from the keyboard: two jumps while walking right
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin))
+///   .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(16)))
+///   .init_resource::<Recording>()
+///   .add_systems(Update, record);
+/// for frame in 0..8 {
+///   let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+///   match frame {
+///     1 | 5 => keyboard.press(KeyCode::Q),
+///     3 | 6 => keyboard.release(KeyCode::Q),
+///     _ => {}
+///   }
+///   match frame {
+///     2 => keyboard.press(KeyCode::N),
+///     6 => keyboard.release(KeyCode::N),
+///     _ => {}
+///   }
+///   app.update();
+/// }
+/// let recording = app.world.remove_resource::<Recording>().unwrap().0;
+///
+/// // Run the game against it
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, InputPlugin, DevcadePlugin))
+///   .init_resource::<Game>()
+///   .add_systems(Update, play);
+/// let mut replay = InputReplay::new(recording);
+/// assert_eq!(replay.remaining(), 8);
+/// replay.run(&mut app);
+/// assert_eq!(replay.remaining(), 0);
+/// assert!(!replay.step(&mut app));
+///
+/// let game = app.world.resource::<Game>();
+/// assert_eq!(game.jumps, 2);
+/// assert_eq!(game.x, 4.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputReplay {
+  frames: Vec<InputSnapshot>,
+  /// Index of the next frame to play
+  next: usize,
+}
+
+impl InputReplay {
+  /// Creates a replay of `frames`, in the order given
+  pub fn new(frames: impl IntoIterator<Item = InputSnapshot>) -> Self {
+    Self {
+      frames: frames.into_iter().collect(),
+      next: 0,
+    }
+  }
+
+  /// Presses the next recorded frame's buttons and updates `app` once.
+  /// Returns false, without updating, once every frame has been played
+  pub fn step(&mut self, app: &mut App) -> bool {
+    let Some(frame) = self.frames.get(self.next) else {
+      return false;
+    };
+    let previous = match self.next.checked_sub(1) {
+      Some(previous) => self.frames[previous].time,
+      None => Duration::ZERO,
+    };
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+      frame.time.saturating_sub(previous),
+    ));
+    let mut overrides = app.world.resource_mut::<InputOverride>();
+    for player in enum_iterator::all::<Player>() {
+      for &button in frame.pressed(player) {
+        overrides.press_for_frames(player, button, 1);
+      }
+    }
+    self.next += 1;
+    app.update();
+    true
+  }
+
+  /// Plays every frame that hasn't been played yet
+  pub fn run(&mut self, app: &mut App) {
+    while self.step(app) {}
+  }
+
+  /// How many frames are left to play
+  pub fn remaining(&self) -> usize {
+    self.frames.len() - self.next
+  }
+}
@@ -0,0 +1,434 @@
+use crate::{
+  AxisConfig, Button, DevcadeInputConfig, InputOverride, KeyboardBindings, Player, SocdMode,
+  StickMode,
+};
+use bevy::prelude::*;
+
+/// Works out whether Devcade buttons are pressed from bevy's input resources
+///
+/// This is the logic behind [`DevcadeControls`](crate::DevcadeControls),
+/// for building your own [`SystemParam`](bevy::ecs::system::SystemParam)s or
+/// reading buttons somewhere `DevcadeControls` can't be used. It has no
+/// memory between frames, so it can only tell whether a button is pressed,
+/// not whether it was just pressed.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{
+///   Button, ButtonResolver, DevcadeInputConfig, InputOverride, KeyboardBindings, Player,
+/// };
+///
+/// fn input_system(
+///   gamepads: Res<Gamepads>,
+///   button_inputs: Res<Input<GamepadButton>>,
+///   button_axes: Res<Axis<GamepadButton>>,
+///   axes: Res<Axis<GamepadAxis>>,
+///   keyboard_input: Res<Input<KeyCode>>,
+///   keyboard_bindings: Res<KeyboardBindings>,
+///   input_override: Res<InputOverride>,
+///   config: Res<DevcadeInputConfig>,
+/// ) {
+///   let resolver = ButtonResolver {
+///     gamepads: &gamepads,
+///     button_inputs: &button_inputs,
+///     button_axes: &button_axes,
+///     axes: &axes,
+///     keyboard_input: &keyboard_input,
+///     keyboard_bindings: &keyboard_bindings,
+///     input_override: &input_override,
+///     config: &config,
+///   };
+///   let gamepad = resolver.default_gamepad(Player::P1);
+///   if resolver.pressed(Button::A1, Player::P1, gamepad) {
+///     println!("P1 is pressing A1");
+///   }
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct ButtonResolver<'a> {
+  /// Connected gamepads
+  pub gamepads: &'a Gamepads,
+  /// Gamepad button states
+  pub button_inputs: &'a Input<GamepadButton>,
+  /// How far analog gamepad buttons, like triggers, are pressed
+  pub button_axes: &'a Axis<GamepadButton>,
+  /// Gamepad axis values
+  pub axes: &'a Axis<GamepadAxis>,
+  /// Keyboard key states
+  pub keyboard_input: &'a Input<KeyCode>,
+  /// Keys to read for each button when using the keyboard
+  pub keyboard_bindings: &'a KeyboardBindings,
+  /// Simulated button presses
+  pub input_override: &'a InputOverride,
+  /// Settings for reading input
+  pub config: &'a DevcadeInputConfig,
+}
+
+impl<'a> ButtonResolver<'a> {
+  /// Returns every connected gamepad, ordered by id. Players are assigned
+  /// gamepads in this order
+  pub fn connected_gamepads(&self) -> Vec<Gamepad> {
+    let mut gamepads: Vec<_> = self.gamepads.iter().collect();
+    gamepads.sort_by_key(|gamepad| gamepad.id);
+    gamepads
+  }
+
+  /// Returns the gamepad the player would be assigned given the gamepads
+  /// connected right now. This is the first of
+  /// [`ButtonResolver::default_gamepads`].
+  ///
+  /// [`DevcadeControls`](crate::DevcadeControls) waits for
+  /// [`DevcadeInputConfig::gamepad_settle`] before changing assignments, so
+  /// this can briefly disagree with it.
+  pub fn default_gamepad(&self, player: Player) -> Option<Gamepad> {
+    self.default_gamepads(player).first().copied()
+  }
+
+  /// Returns every gamepad the player would be assigned given the gamepads
+  /// connected right now, which is more than one when
+  /// [`DevcadeInputConfig::gamepads_per_player`] is
+  pub fn default_gamepads(&self, player: Player) -> Vec<Gamepad> {
+    let per_player = self.config.gamepads_per_player.max(1);
+    self
+      .connected_gamepads()
+      .into_iter()
+      .skip(player.index() * per_player)
+      .take(per_player)
+      .collect()
+  }
+
+  /// Returns true if the button is pressed by the given player using
+  /// `gamepad`.
+  /// Uses keyboard if no controller is plugged in and
+  /// [`DevcadeInputConfig::keyboard_fallback`] is set, or in addition to the
+  /// controller if [`DevcadeInputConfig::merge_keyboard_and_gamepad`] is set.
+  /// See [`KeyboardBindings`] for the keyboard mappings
+  /// and [`InputOverride`] for simulated presses.
+  ///
+  /// Stick directions are pressed when [`ButtonResolver::stick`] points that
+  /// way. For players in [`DevcadeInputConfig::mirror_layout`], `button` is
+  /// read from the [mirrored](Button::mirrored) physical control.
+  pub fn pressed(&self, button: Button, player: Player, gamepad: Option<Gamepad>) -> bool {
+    self.pressed_on(button, player, gamepad.as_slice())
+  }
+
+  /// Like [`ButtonResolver::pressed`], for a player using several gamepads at
+  /// once. The button is pressed if it's pressed on any of them. With no
+  /// gamepads, this reads the keyboard like a player without a controller
+  pub fn pressed_on(&self, button: Button, player: Player, gamepads: &[Gamepad]) -> bool {
+    if let Ok(axis_config) = AxisConfig::try_from(&button) {
+      return axis_config.pressed(self.stick_on(player, gamepads));
+    }
+    if self.input_override.pressed(player, button) {
+      return true;
+    }
+    let button = if self.config.mirror_layout.contains(&player) {
+      button.mirrored()
+    } else {
+      button
+    };
+    if gamepads.is_empty() {
+      self.config.keyboard_fallback && self.keyboard_pressed(button, player)
+    } else {
+      gamepads
+        .iter()
+        .any(|&gamepad| self.gamepad_button_pressed(gamepad, button))
+        || (self.config.merge_keyboard_and_gamepad && self.keyboard_pressed(button, player))
+    }
+  }
+
+  /// Returns the position of the player's stick, from -1 to 1 on each axis.
+  ///
+  /// Combines the same sources as [`ButtonResolver::pressed`]. Keyboard and
+  /// overridden directions push the stick all the way, and opposite
+  /// directions cancel out. The controller's stick is inverted and has its
+  /// deadzone applied according to the [`DevcadeInputConfig`], then the
+  /// [`stick_curve`](DevcadeInputConfig::stick_curve) is applied, and the result is snapped to one
+  /// axis in [`StickMode::FourWay`].
+  pub fn stick(&self, player: Player, gamepad: Option<Gamepad>) -> Vec2 {
+    self.stick_on(player, gamepad.as_slice())
+  }
+
+  /// Like [`ButtonResolver::stick`], for a player using several gamepads at
+  /// once. Each axis is read from the first gamepad where it's pushed past
+  /// the deadzone
+  pub fn stick_on(&self, player: Player, gamepads: &[Gamepad]) -> Vec2 {
+    self.stick_with_socd(player, gamepads, &mut SocdState::default())
+  }
+
+  /// Like [`ButtonResolver::stick_on`], resolving opposite keyboard
+  /// directions according to [`DevcadeInputConfig::socd`]. `socd` remembers
+  /// what was held on the previous frame. Without it, opposite directions
+  /// can only cancel out
+  pub(crate) fn stick_with_socd(
+    &self,
+    player: Player,
+    gamepads: &[Gamepad],
+    socd: &mut SocdState,
+  ) -> Vec2 {
+    let mut stick = Vec2::ZERO;
+    for &gamepad in gamepads {
+      let gamepad_stick = self.gamepad_stick(gamepad);
+      if stick.x == 0.0 {
+        stick.x = gamepad_stick.x;
+      }
+      if stick.y == 0.0 {
+        stick.y = gamepad_stick.y;
+      }
+    }
+    let use_keyboard = if gamepads.is_empty() {
+      self.config.keyboard_fallback
+    } else {
+      self.config.merge_keyboard_and_gamepad
+    };
+    if use_keyboard {
+      stick += socd.resolve(self.config.socd, |button| {
+        self.keyboard_pressed(button, player)
+      });
+    } else {
+      *socd = SocdState::default();
+    }
+    if self.config.mirror_layout.contains(&player) {
+      stick.x = -stick.x;
+    }
+    stick = self
+      .config
+      .apply_stick_response(stick.clamp(Vec2::NEG_ONE, Vec2::ONE));
+
+    let overridden = self.digital_stick(|button| self.input_override.pressed(player, button));
+    if overridden.x != 0.0 {
+      stick.x = overridden.x;
+    }
+    if overridden.y != 0.0 {
+      stick.y = overridden.y;
+    }
+
+    self.snap_stick(stick)
+  }
+
+  /// Returns the position of the player's right stick, for controllers that
+  /// have one, from -1 to 1 on each axis
+  ///
+  /// Read the same way as the controller's part of [`ButtonResolver::stick`],
+  /// with the deadzone, inversion and mirroring applied, but without the
+  /// [`stick_curve`](DevcadeInputConfig::stick_curve) or [`StickMode`]. The keyboard and
+  /// [`InputOverride`] never move it.
+  pub fn right_stick(&self, player: Player, gamepad: Option<Gamepad>) -> Vec2 {
+    self.right_stick_on(player, gamepad.as_slice())
+  }
+
+  /// Like [`ButtonResolver::right_stick`], for a player using several
+  /// gamepads at once. Each axis is read from the first gamepad where it's
+  /// pushed past the deadzone
+  pub fn right_stick_on(&self, player: Player, gamepads: &[Gamepad]) -> Vec2 {
+    let mut stick = Vec2::ZERO;
+    for &gamepad in gamepads {
+      let gamepad_stick = self.gamepad_axes(
+        gamepad,
+        GamepadAxisType::RightStickX,
+        GamepadAxisType::RightStickY,
+      );
+      if stick.x == 0.0 {
+        stick.x = gamepad_stick.x;
+      }
+      if stick.y == 0.0 {
+        stick.y = gamepad_stick.y;
+      }
+    }
+    if self.config.mirror_layout.contains(&player) {
+      stick.x = -stick.x;
+    }
+    stick
+  }
+
+  /// Returns true if `button` is pressed on `gamepad`, whether or not it
+  /// belongs to a player. Useful for controllers that aren't used for
+  /// playing, like one for an attendant.
+  ///
+  /// Reads the controller the same way as [`ButtonResolver::pressed`], but
+  /// never reads the keyboard or [`InputOverride`], and ignores
+  /// [`DevcadeInputConfig::mirror_layout`].
+  ///
+  /// # Examples
+  /// ```
+  /// # use bevy::prelude::*;
+  /// # use devcaders::{Button, ButtonResolver};
+  /// // The attendant's controller doesn't belong to a player
+  /// fn attendant_opened_menu(resolver: &ButtonResolver, attendant: Gamepad) -> bool {
+  ///   resolver.gamepad_pressed(attendant, Button::Menu)
+  /// }
+  /// ```
+  pub fn gamepad_pressed(&self, gamepad: Gamepad, button: Button) -> bool {
+    match AxisConfig::try_from(&button) {
+      Ok(axis_config) => axis_config.pressed(self.snap_stick(self.gamepad_stick(gamepad))),
+      Err(_) => self.gamepad_button_pressed(gamepad, button),
+    }
+  }
+
+  /// Snaps `stick` to one axis in [`StickMode::FourWay`]
+  fn snap_stick(&self, mut stick: Vec2) -> Vec2 {
+    if self.config.stick_mode == StickMode::FourWay {
+      if stick.x.abs() >= stick.y.abs() {
+        stick.y = 0.0;
+      } else {
+        stick.x = 0.0;
+      }
+    }
+    stick
+  }
+
+  fn gamepad_button_pressed(&self, gamepad: Gamepad, button: Button) -> bool {
+    let Ok(button_type) = GamepadButtonType::try_from(&button) else {
+      return false;
+    };
+    let button = GamepadButton::new(gamepad, button_type);
+    let is_trigger = matches!(
+      button_type,
+      GamepadButtonType::LeftTrigger
+        | GamepadButtonType::LeftTrigger2
+        | GamepadButtonType::RightTrigger
+        | GamepadButtonType::RightTrigger2
+    );
+    match (self.config.trigger_threshold, self.button_axes.get(button)) {
+      (Some(threshold), Some(value)) if is_trigger => value >= threshold,
+      _ => self.button_inputs.pressed(button),
+    }
+  }
+
+  /// Reads the gamepad's stick, inverted and with the deadzone applied
+  /// according to the config
+  fn gamepad_stick(&self, gamepad: Gamepad) -> Vec2 {
+    self.gamepad_axes(
+      gamepad,
+      GamepadAxisType::LeftStickX,
+      GamepadAxisType::LeftStickY,
+    )
+  }
+
+  /// Reads a pair of the gamepad's axes as a stick, inverted and with the
+  /// deadzone applied according to the config
+  fn gamepad_axes(&self, gamepad: Gamepad, x: GamepadAxisType, y: GamepadAxisType) -> Vec2 {
+    let axis = |axis_type| {
+      let value = self
+        .axes
+        .get(GamepadAxis::new(gamepad, axis_type))
+        .unwrap_or(0.0);
+      if value.abs() <= self.config.deadzone {
+        0.0
+      } else {
+        value
+      }
+    };
+    let mut stick = Vec2::new(axis(x), axis(y));
+    if self.config.invert_x {
+      stick.x = -stick.x;
+    }
+    if self.config.invert_y {
+      stick.y = -stick.y;
+    }
+    stick
+  }
+
+  /// Builds a stick position out of which stick directions are pressed
+  fn digital_stick(&self, pressed: impl Fn(Button) -> bool) -> Vec2 {
+    let axis = |positive, negative| match (pressed(positive), pressed(negative)) {
+      (true, false) => 1.0,
+      (false, true) => -1.0,
+      _ => 0.0,
+    };
+    Vec2::new(
+      axis(Button::StickRight, Button::StickLeft),
+      axis(Button::StickUp, Button::StickDown),
+    )
+  }
+
+  fn keyboard_pressed(&self, button: Button, player: Player) -> bool {
+    self
+      .keyboard_bindings
+      .pressed(player, button, self.keyboard_input)
+  }
+}
+
+/// What the digital stick directions were doing on the previous frame, for
+/// resolving opposite directions with [`SocdMode`]
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SocdState {
+  /// Whether right, left, up and down were held
+  held: [bool; 4],
+  /// The stick they resolved to
+  resolved: Vec2,
+}
+
+impl SocdState {
+  /// Builds a stick position out of which stick directions are pressed,
+  /// resolving opposite directions according to `mode`
+  fn resolve(&mut self, mode: SocdMode, pressed: impl Fn(Button) -> bool) -> Vec2 {
+    let held = [
+      Button::StickRight,
+      Button::StickLeft,
+      Button::StickUp,
+      Button::StickDown,
+    ]
+    .map(pressed);
+    let axis = |positive: usize, resolved: f32| {
+      let (now, before) = (
+        (held[positive], held[positive + 1]),
+        (self.held[positive], self.held[positive + 1]),
+      );
+      match (now, mode, before) {
+        ((true, false), _, _) => 1.0,
+        ((false, true), _, _) => -1.0,
+        ((false, false), _, _) | (_, SocdMode::Neutral, _) => 0.0,
+        // Both are held. Going by which was held before
+        (_, _, (true, true)) => resolved,
+        (_, SocdMode::LastWins, (true, false)) | (_, SocdMode::FirstWins, (false, true)) => -1.0,
+        (_, SocdMode::LastWins, (false, true)) | (_, SocdMode::FirstWins, (true, false)) => 1.0,
+        (_, _, (false, false)) => 0.0,
+      }
+    };
+    let stick = Vec2::new(axis(0, self.resolved.x), axis(2, self.resolved.y));
+    *self = Self {
+      held,
+      resolved: stick,
+    };
+    stick
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fake_gamepad::{connect_gamepad, press_gamepad_button, set_axis};
+  use bevy::input::InputPlugin;
+
+  #[test]
+  fn gamepad_pressed_reads_any_gamepad() {
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, InputPlugin))
+      .init_resource::<KeyboardBindings>()
+      .init_resource::<InputOverride>()
+      .init_resource::<DevcadeInputConfig>();
+    let attendant = Gamepad::new(7);
+    connect_gamepad(&mut app, attendant);
+    press_gamepad_button(&mut app, attendant, GamepadButtonType::Start);
+    set_axis(&mut app, attendant, GamepadAxisType::LeftStickX, -1.0);
+
+    let world = &app.world;
+    let resolver = ButtonResolver {
+      gamepads: world.resource(),
+      button_inputs: world.resource(),
+      button_axes: world.resource(),
+      axes: world.resource(),
+      keyboard_input: world.resource(),
+      keyboard_bindings: world.resource(),
+      input_override: world.resource(),
+      config: world.resource(),
+    };
+    assert!(resolver.gamepad_pressed(attendant, Button::Menu));
+    assert!(resolver.gamepad_pressed(attendant, Button::StickLeft));
+    assert!(!resolver.gamepad_pressed(attendant, Button::A1));
+    assert!(!resolver.gamepad_pressed(attendant, Button::StickRight));
+  }
+}
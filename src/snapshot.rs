@@ -0,0 +1,57 @@
+use crate::{Button, Player};
+use bevy::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The state of both players' controls on one frame, from
+/// [`DevcadeControls::snapshot`](crate::DevcadeControls::snapshot)
+///
+/// With the `serialize` feature this can be written out, e.g. as JSON to
+/// attach a frame of input to a bug report.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct InputSnapshot {
+  /// How many frames the system had seen before this one
+  pub frame: u64,
+  /// Time since startup when the frame ran
+  pub time: Duration,
+  p1: Vec<Button>,
+  p2: Vec<Button>,
+  p1_stick: Vec2,
+  p2_stick: Vec2,
+}
+
+impl InputSnapshot {
+  pub(crate) fn new(
+    frame: u64,
+    time: Duration,
+    pressed: impl Fn(Player) -> Vec<Button>,
+    stick: impl Fn(Player) -> Vec2,
+  ) -> Self {
+    Self {
+      frame,
+      time,
+      p1: pressed(Player::P1),
+      p2: pressed(Player::P2),
+      p1_stick: stick(Player::P1),
+      p2_stick: stick(Player::P2),
+    }
+  }
+
+  /// The buttons `player` was holding on this frame
+  pub fn pressed(&self, player: Player) -> &[Button] {
+    match player {
+      Player::P1 => &self.p1,
+      Player::P2 => &self.p2,
+    }
+  }
+
+  /// Where `player`'s stick was on this frame
+  pub fn stick(&self, player: Player) -> Vec2 {
+    match player {
+      Player::P1 => self.p1_stick,
+      Player::P2 => self.p2_stick,
+    }
+  }
+}
@@ -0,0 +1,207 @@
+use crate::BackendClient;
+use devcade_onboard_types::{Request, RequestBody, Response, ResponseBody};
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Backend for the crate's own tests that hands every request to a handler,
+/// for tests that need more control than `FakeBackendServer`'s scripted
+/// responses
+///
+/// Each connection is read on its own thread, so a handler that takes its
+/// time holds up later requests on the same connection only.
+pub(crate) struct TestBackend {
+  address: Address,
+  connections: Arc<Mutex<Vec<Connection>>>,
+}
+
+enum Address {
+  Unix(PathBuf),
+  Tcp(String),
+}
+
+impl TestBackend {
+  /// Listens on a new Unix socket in the temp directory, calling `handler`
+  /// with each request
+  pub(crate) fn serve(handler: impl Fn(Request, &Connection) + Send + Sync + 'static) -> Self {
+    static SOCKET_COUNT: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+      "devcaders-test-backend-{}-{}.sock",
+      std::process::id(),
+      SOCKET_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).expect("Couldn't bind test backend socket");
+    let backend = Self {
+      address: Address::Unix(path),
+      connections: Default::default(),
+    };
+    backend.accept(
+      move || listener.accept().map(|(stream, _)| Stream::Unix(stream)),
+      handler,
+    );
+    backend
+  }
+
+  /// Like [`TestBackend::serve`], but listens on a local TCP port
+  pub(crate) fn serve_tcp(handler: impl Fn(Request, &Connection) + Send + Sync + 'static) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't bind test backend port");
+    let address = listener.local_addr().unwrap().to_string();
+    let backend = Self {
+      address: Address::Tcp(address),
+      connections: Default::default(),
+    };
+    backend.accept(
+      move || listener.accept().map(|(stream, _)| Stream::Tcp(stream)),
+      handler,
+    );
+    backend
+  }
+
+  /// Answers every request straight away with `respond(body)`
+  pub(crate) fn respond(
+    respond: impl Fn(&RequestBody) -> ResponseBody + Send + Sync + 'static,
+  ) -> Self {
+    Self::serve(move |request, connection| {
+      let body = respond(&request.body);
+      connection.reply(&request, body);
+    })
+  }
+
+  fn accept(
+    &self,
+    mut accept: impl FnMut() -> io::Result<Stream> + Send + 'static,
+    handler: impl Fn(Request, &Connection) + Send + Sync + 'static,
+  ) {
+    let handler = Arc::new(handler);
+    let connections = self.connections.clone();
+    std::thread::spawn(move || {
+      while let Ok(stream) = accept() {
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        let connection = Connection(Arc::new(Mutex::new(stream)));
+        connections.lock().unwrap().push(connection.clone());
+        let handler = handler.clone();
+        std::thread::spawn(move || {
+          for line in reader.lines() {
+            let Ok(line) = line else {
+              break;
+            };
+            let request = serde_json::from_str(&line).expect("Couldn't decode request");
+            handler(request, &connection);
+          }
+        });
+      }
+    });
+  }
+
+  /// Creates a new client connected to this backend
+  pub(crate) fn client(&self) -> BackendClient {
+    match &self.address {
+      Address::Unix(path) => BackendClient::with_path(path),
+      Address::Tcp(address) => BackendClient::with_tcp(address),
+    }
+  }
+
+  /// Returns how many connections have been made so far
+  pub(crate) fn connections(&self) -> usize {
+    self.connections.lock().unwrap().len()
+  }
+
+  /// Hangs up on every client
+  pub(crate) fn disconnect(&self) {
+    for connection in self.connections.lock().unwrap().iter() {
+      connection.hang_up();
+    }
+  }
+}
+
+impl Drop for TestBackend {
+  fn drop(&mut self) {
+    self.disconnect();
+    if let Address::Unix(path) = &self.address {
+      let _ = std::fs::remove_file(path);
+    }
+  }
+}
+
+/// One client's connection to a [`TestBackend`]
+#[derive(Clone)]
+pub(crate) struct Connection(Arc<Mutex<Stream>>);
+
+impl Connection {
+  /// Answers `request` with `body`
+  pub(crate) fn reply(&self, request: &Request, body: ResponseBody) {
+    let response = Response {
+      request_id: request.request_id,
+      body,
+    };
+    self.send_line(&serde_json::to_string(&response).unwrap());
+  }
+
+  /// Sends `line` as is, whether or not it's a valid response. Ignores
+  /// clients that have gone away
+  pub(crate) fn send_line(&self, line: &str) {
+    let _ = writeln!(self.0.lock().unwrap(), "{line}");
+  }
+
+  /// Closes the connection
+  pub(crate) fn hang_up(&self) {
+    self.0.lock().unwrap().shutdown();
+  }
+}
+
+enum Stream {
+  Unix(UnixStream),
+  Tcp(TcpStream),
+}
+
+impl Stream {
+  fn try_clone(&self) -> io::Result<Self> {
+    Ok(match self {
+      Self::Unix(stream) => Self::Unix(stream.try_clone()?),
+      Self::Tcp(stream) => Self::Tcp(stream.try_clone()?),
+    })
+  }
+
+  fn shutdown(&self) {
+    let _ = match self {
+      Self::Unix(stream) => stream.shutdown(Shutdown::Both),
+      Self::Tcp(stream) => stream.shutdown(Shutdown::Both),
+    };
+  }
+}
+
+impl Read for Stream {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    match self {
+      Self::Unix(stream) => stream.read(buf),
+      Self::Tcp(stream) => stream.read(buf),
+    }
+  }
+}
+
+impl Write for Stream {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    match self {
+      Self::Unix(stream) => stream.write(buf),
+      Self::Tcp(stream) => stream.write(buf),
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    match self {
+      Self::Unix(stream) => stream.flush(),
+      Self::Tcp(stream) => stream.flush(),
+    }
+  }
+}
+
+/// Runs `future` to completion inside a tokio context, like the crate's own
+/// tasks do
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  futures_lite::future::block_on(async_compat::Compat::new(future))
+}
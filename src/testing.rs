@@ -0,0 +1,200 @@
+use crate::BackendClient;
+use devcade_onboard_types::{Request, RequestBody, ResponseBody};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Builder for a [`FakeBackendServer`]
+#[derive(Default)]
+pub struct FakeBackendServerBuilder {
+  responses: Vec<(Value, VecDeque<Value>)>,
+}
+
+impl FakeBackendServerBuilder {
+  /// Responds to requests with the body `request` with `response`.
+  ///
+  /// If several responses are given for the same request, they're used in
+  /// order, and the last one is repeated once the rest have been used.
+  pub fn respond(mut self, request: RequestBody, response: ResponseBody) -> Self {
+    let request = serde_json::to_value(request).expect("Couldn't serialize RequestBody?");
+    let response = serde_json::to_value(response).expect("Couldn't serialize ResponseBody?");
+    match self.responses.iter_mut().find(|(body, _)| *body == request) {
+      Some((_, responses)) => responses.push_back(response),
+      None => self.responses.push((request, VecDeque::from([response]))),
+    }
+    self
+  }
+
+  /// Starts listening on a new socket in the temp directory
+  pub fn start(self) -> FakeBackendServer {
+    static SERVER_COUNT: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+      "devcaders-fake-backend-{}-{}.sock",
+      std::process::id(),
+      SERVER_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).expect("Couldn't bind fake backend socket");
+
+    let state = Arc::new(Mutex::new(ServerState {
+      responses: self.responses,
+      requests: Vec::new(),
+    }));
+    let stopped = Arc::new(AtomicBool::new(false));
+    {
+      let state = state.clone();
+      let stopped = stopped.clone();
+      std::thread::spawn(move || {
+        for stream in listener.incoming() {
+          if stopped.load(Ordering::Acquire) {
+            break;
+          }
+          match stream {
+            Ok(stream) => {
+              let state = state.clone();
+              std::thread::spawn(move || serve_connection(stream, &state));
+            }
+            Err(err) => log::error!("Fake backend couldn't accept a connection. {err}"),
+          }
+        }
+      });
+    }
+
+    FakeBackendServer {
+      path,
+      state,
+      stopped,
+    }
+  }
+}
+
+struct ServerState {
+  responses: Vec<(Value, VecDeque<Value>)>,
+  requests: Vec<RequestBody>,
+}
+
+impl ServerState {
+  fn respond(&mut self, request: RequestBody) -> Value {
+    let key = serde_json::to_value(&request).expect("Couldn't serialize RequestBody?");
+    let response = match self.responses.iter_mut().find(|(body, _)| *body == key) {
+      Some((_, responses)) if responses.len() > 1 => responses.pop_front(),
+      Some((_, responses)) => responses.front().cloned(),
+      None => None,
+    };
+    let response = response.unwrap_or_else(|| {
+      let err = ResponseBody::Err(format!("No scripted response for {request}"));
+      serde_json::to_value(err).expect("Couldn't serialize ResponseBody?")
+    });
+    self.requests.push(request);
+    response
+  }
+}
+
+fn serve_connection(mut stream: UnixStream, state: &Mutex<ServerState>) {
+  let reader = match stream.try_clone() {
+    Ok(reader) => BufReader::new(reader),
+    Err(err) => {
+      log::error!("Fake backend couldn't read from a connection. {err}");
+      return;
+    }
+  };
+  for line in reader.lines() {
+    let Ok(line) = line else {
+      break;
+    };
+    let request: Request = match serde_json::from_str(&line) {
+      Ok(request) => request,
+      Err(err) => {
+        log::error!("Fake backend couldn't decode request ({line}) {err}");
+        continue;
+      }
+    };
+    let mut response = state.lock().unwrap().respond(request.body);
+    response["request_id"] = request.request_id.into();
+    if writeln!(stream, "{response}").is_err() {
+      break;
+    }
+  }
+}
+
+/// Stand-in for the Devcade backend that answers requests with scripted
+/// responses, for testing code that talks to the backend
+///
+/// Requests without a scripted response are answered with
+/// [`ResponseBody::Err`]. The server stops and removes its socket when
+/// dropped.
+///
+/// Use [`FakeBackendServer::client`] to get a client connected to it. To
+/// test systems that talk to the backend, insert that client as the
+/// [`BackendClientResource`](crate::BackendClientResource) and have them
+/// send their requests with it, like
+/// [`NfcTagRequestComponent::with_client`](crate::NfcTagRequestComponent::with_client)
+/// does. Pointing `DEVCADE_ONBOARD_PATH` at the server instead only works
+/// for the first server in a process, since the shared client is created
+/// once.
+///
+/// # Example
+/// ```
+/// use devcaders::devcade_onboard_types::{Player as BackendPlayer, RequestBody, ResponseBody};
+/// use devcaders::FakeBackendServer;
+///
+/// let server = FakeBackendServer::builder()
+///   .respond(RequestBody::Ping, ResponseBody::Pong)
+///   .respond(
+///     RequestBody::GetNfcTag(BackendPlayer::P1),
+///     ResponseBody::NfcTag(Some("abc".to_owned())),
+///   )
+///   .start();
+///
+/// let client = server.client();
+/// futures_lite::future::block_on(async_compat::Compat::new(async {
+///   let pong = client.send(RequestBody::Ping).await;
+///   assert!(matches!(pong, Ok(ResponseBody::Pong)));
+///   let tag = client.send(RequestBody::GetNfcTag(BackendPlayer::P1)).await;
+///   assert!(matches!(tag, Ok(ResponseBody::NfcTag(Some(tag))) if tag == "abc"));
+///   let user = client.send(RequestBody::GetNfcUser("abc".to_owned())).await;
+///   assert!(user.is_err());
+/// }));
+///
+/// assert_eq!(server.requests().len(), 3);
+/// ```
+pub struct FakeBackendServer {
+  path: PathBuf,
+  state: Arc<Mutex<ServerState>>,
+  stopped: Arc<AtomicBool>,
+}
+
+impl FakeBackendServer {
+  /// Creates a builder to script the server's responses
+  pub fn builder() -> FakeBackendServerBuilder {
+    FakeBackendServerBuilder::default()
+  }
+
+  /// Path of the server's socket
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Creates a new client connected to this server
+  pub fn client(&self) -> BackendClient {
+    BackendClient::with_path(&self.path)
+  }
+
+  /// Returns every request the server has received so far, oldest first
+  pub fn requests(&self) -> Vec<RequestBody> {
+    self.state.lock().unwrap().requests.clone()
+  }
+}
+
+impl Drop for FakeBackendServer {
+  fn drop(&mut self) {
+    self.stopped.store(true, Ordering::Release);
+    // Wake the listener up so it notices it's been stopped
+    let _ = UnixStream::connect(&self.path);
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
@@ -0,0 +1,143 @@
+use devcade_onboard_types::{Map, Value};
+use std::fmt;
+use std::ops::Deref;
+
+/// The id the backend gives an NFC tag, used to look up who it belongs to
+///
+/// Kept apart from other strings so a tag id can't be mixed up with, say, a
+/// username when asking for a user. Converts to and from [`String`] freely.
+///
+/// # Examples
+/// ```
+/// use devcaders::AssociationId;
+///
+/// let id = AssociationId::from("badge-1234".to_owned());
+/// assert_eq!(id.as_str(), "badge-1234");
+/// assert_eq!(id.to_string(), "badge-1234");
+/// assert_eq!(id, "badge-1234");
+/// assert!(id.starts_with("badge"));
+/// assert_eq!(String::from(id), "badge-1234");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AssociationId(String);
+
+impl AssociationId {
+  /// Returns the id as a string slice
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl Deref for AssociationId {
+  type Target = str;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl fmt::Display for AssociationId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl From<String> for AssociationId {
+  fn from(id: String) -> Self {
+    Self(id)
+  }
+}
+
+impl From<&str> for AssociationId {
+  fn from(id: &str) -> Self {
+    Self(id.to_owned())
+  }
+}
+
+impl From<AssociationId> for String {
+  fn from(id: AssociationId) -> Self {
+    id.0
+  }
+}
+
+impl PartialEq<str> for AssociationId {
+  fn eq(&self, other: &str) -> bool {
+    self.0 == other
+  }
+}
+
+impl PartialEq<&str> for AssociationId {
+  fn eq(&self, other: &&str) -> bool {
+    self.0 == *other
+  }
+}
+
+/// A user as returned by the backend for an NFC tag
+///
+/// Wraps the raw attribute map with getters for the attributes games commonly
+/// need. The raw attributes are still available through [`Deref`], so
+/// `user["uid"]` keeps working.
+///
+/// # Examples
+/// ```
+/// use devcaders::DevcadeUser;
+///
+/// let user = DevcadeUser::from(
+///   serde_json::json!({"uid": "mstrodl", "cn": "Mary Strodl", "admin": true})
+///     .as_object()
+///     .unwrap()
+///     .clone(),
+/// );
+/// assert_eq!(user.uid(), Some("mstrodl"));
+/// assert_eq!(user.display_name(), Some("Mary Strodl"));
+/// assert!(user.is_admin());
+///
+/// let user = DevcadeUser::from(
+///   serde_json::json!({"uid": "someone"})
+///     .as_object()
+///     .unwrap()
+///     .clone(),
+/// );
+/// assert_eq!(user.display_name(), None);
+/// assert!(!user.is_admin());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DevcadeUser(Map<String, Value>);
+
+impl DevcadeUser {
+  /// The user's username
+  pub fn uid(&self) -> Option<&str> {
+    self.0.get("uid").and_then(Value::as_str)
+  }
+
+  /// The user's full name, suitable for showing on screen
+  pub fn display_name(&self) -> Option<&str> {
+    self.0.get("cn").and_then(Value::as_str)
+  }
+
+  /// Whether the user is a Devcade admin. False if the backend didn't say
+  pub fn is_admin(&self) -> bool {
+    self
+      .0
+      .get("admin")
+      .and_then(Value::as_bool)
+      .unwrap_or(false)
+  }
+
+  /// Returns the raw attribute map
+  pub fn into_inner(self) -> Map<String, Value> {
+    self.0
+  }
+}
+
+impl From<Map<String, Value>> for DevcadeUser {
+  fn from(attributes: Map<String, Value>) -> Self {
+    Self(attributes)
+  }
+}
+
+impl Deref for DevcadeUser {
+  type Target = Map<String, Value>;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
@@ -0,0 +1,158 @@
+use crate::{BackendClientResource, RequestError, ResponseBodyExt};
+use async_compat::Compat;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use devcade_onboard_types::{RequestBody, ResponseBody};
+use futures_lite::future;
+use std::time::{Duration, Instant};
+
+/// Connects to the Devcade backend and pings it as soon as the game starts,
+/// so a loading screen can show whether the backend is up without any async
+/// code of its own
+///
+/// The ping runs on the [`AsyncComputeTaskPool`] and doesn't hold up
+/// startup. Its outcome is kept in the [`BackendStatus`] resource, and the
+/// connection it makes is reused by later requests through the
+/// [`BackendClientResource`].
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use devcaders::{BackendStatus, BackendWarmupPlugin};
+///
+/// fn loading_screen(status: Res<BackendStatus>) {
+///   if status.is_connecting() {
+///     println!("Connecting to the backend...");
+///   }
+/// }
+///
+/// let mut app = App::new();
+/// app
+///   .add_plugins((MinimalPlugins, BackendWarmupPlugin::default()))
+///   .add_systems(Update, loading_screen);
+/// ```
+pub struct BackendWarmupPlugin {
+  /// How long to wait for the backend to answer before counting it as
+  /// unavailable. Defaults to 5 seconds
+  pub timeout: Duration,
+}
+
+impl Default for BackendWarmupPlugin {
+  fn default() -> Self {
+    Self {
+      timeout: Duration::from_secs(5),
+    }
+  }
+}
+
+impl Plugin for BackendWarmupPlugin {
+  fn build(&self, app: &mut App) {
+    let timeout = self.timeout;
+    app
+      .init_resource::<BackendClientResource>()
+      .init_resource::<BackendStatus>()
+      .add_systems(
+        Startup,
+        move |mut commands: Commands, client: Res<BackendClientResource>| {
+          commands.insert_resource(start_warmup(&client, timeout));
+        },
+      )
+      .add_systems(
+        Update,
+        finish_warmup.run_if(resource_exists::<BackendWarmup>()),
+      );
+  }
+}
+
+/// What [`BackendWarmupPlugin`] found out about the backend
+#[derive(Resource, Debug, Default)]
+pub enum BackendStatus {
+  /// Still waiting for the backend to answer
+  #[default]
+  Connecting,
+  /// The backend answered
+  Connected {
+    /// How long it took to connect and answer the ping
+    ping: Duration,
+  },
+  /// The backend couldn't be reached or didn't answer in time
+  Unavailable(RequestError),
+}
+
+impl BackendStatus {
+  /// Returns true until the backend has answered or failed to
+  pub fn is_connecting(&self) -> bool {
+    matches!(self, Self::Connecting)
+  }
+
+  /// Returns true if the backend answered
+  pub fn is_connected(&self) -> bool {
+    matches!(self, Self::Connected { .. })
+  }
+}
+
+/// The ping in progress
+#[derive(Resource)]
+struct BackendWarmup(Task<Result<Duration, RequestError>>);
+
+fn start_warmup(client: &BackendClientResource, timeout: Duration) -> BackendWarmup {
+  let client = client.0.clone();
+  BackendWarmup(AsyncComputeTaskPool::get().spawn(Compat::new(async move {
+    let started = Instant::now();
+    client
+      .send_timeout(RequestBody::Ping, timeout)
+      .await
+      .and_then(ResponseBody::expect_pong)
+      .map(|_| started.elapsed())
+  })))
+}
+
+fn finish_warmup(
+  mut commands: Commands,
+  mut warmup: ResMut<BackendWarmup>,
+  mut status: ResMut<BackendStatus>,
+) {
+  let Some(result) = future::block_on(future::poll_once(&mut warmup.0)) else {
+    return;
+  };
+  commands.remove_resource::<BackendWarmup>();
+  *status = match result {
+    Ok(ping) => BackendStatus::Connected { ping },
+    Err(err) => {
+      log::warn!("Couldn't reach the backend at startup. {err}");
+      BackendStatus::Unavailable(err)
+    }
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_backend::TestBackend;
+  use crate::BackendClient;
+
+  fn wait_for_status(client: BackendClient) -> BackendStatus {
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, BackendWarmupPlugin::default()))
+      .insert_resource(BackendClientResource::new(client));
+    app.update();
+    while app.world.resource::<BackendStatus>().is_connecting() {
+      std::thread::sleep(Duration::from_millis(10));
+      app.update();
+    }
+    app.world.remove_resource::<BackendStatus>().unwrap()
+  }
+
+  #[test]
+  fn reports_whether_backend_answered() {
+    let backend = TestBackend::respond(|_| ResponseBody::Pong);
+    let status = wait_for_status(backend.client());
+    assert!(matches!(status, BackendStatus::Connected { .. }));
+    assert!(status.is_connected());
+
+    let missing = std::env::temp_dir().join("devcaders-warmup-missing.sock");
+    let status = wait_for_status(BackendClient::with_path(missing));
+    assert!(matches!(status, BackendStatus::Unavailable(_)));
+  }
+}